@@ -1,28 +1,77 @@
+use std::collections::HashMap;
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, RwLock,
 };
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 // 添加专用线程池支持
 use std::thread;
 
-// 添加高精度计时器支持
-#[cfg(target_os = "windows")]
-use std::time::Instant;
-
 use crate::{
-    util::gamepad_util::{polling_rate_log_to_output_log, PollingRateLog, PollingRateResult},
+    util::calibration::{CalibrationPhase, CalibrationSession, CalibrationStatus},
+    util::capabilities::DeviceCapabilities,
+    util::coupling::CouplingReport,
+    util::error::GamepadError,
+    util::gamepad_util::{
+        polling_rate_log_to_output_log, ConnectionStatus, GamepadInfo, PollingRateResult,
+        SampleSource,
+    },
+    util::health::HealthReport,
+    util::led::LedInfo,
+    util::mapping::{AppliedMapping, MappingStore},
+    util::mapping_wizard::{MappingWizard, WizardPhase, WizardStatus},
+    util::motion::MotionStats,
+    util::power::BatteryReport,
+    util::profile::{AxisCalibration, GamepadProfile, ProfileLoadError, ProfileStore},
+    util::rumble::RumblePattern,
+    util::sampler::{calibrate, Sampler, SamplerCalibration, SamplerConfig, SamplerStats},
+    util::settings::{RejectedSetting, Settings, SettingsStore},
+    util::snapback::StickSnapback,
+    util::spectrum::Spectrum,
+    util::touchpad::TouchpadStats,
+    util::transform::TransformSettings,
     GamepadState,
 };
 use tokio::time::{self, Duration};
 
+#[cfg(target_os = "linux")]
+use crate::util::sampler::SamplerStrategy;
+
 const STANDBY_SLEEP_TIME: u64 = 10000;
-const POLLING_RATE_MICROSECONDS: u64 = 250;
+const MAX_CALIBRATION_TIME: u64 = 60000; // ms
+const SAMPLER_STATS_INTERVAL: u64 = 1000; // 每 1000 次采样同步一次采样器统计
+#[cfg(target_os = "linux")]
+const EVENT_READ_TIMEOUT: u64 = 100; // ms, 超时后检查停止标志
+const WIZARD_POLL_INTERVAL: u64 = 10; // ms
+const CALIBRATION_POLL_INTERVAL: u64 = 50; // ms
+const SPECTRUM_INTERVAL: Duration = Duration::from_millis(500); // 频谱计算量较大，降低发送频率
+const RUMBLE_OVERLAP: Duration = Duration::from_millis(50); // 每段多保持一段时间，避免切换前马达停下
+#[cfg(target_os = "linux")]
+const SUB_DEVICE_RETRY_INTERVAL: u64 = 1000; // ms, 没有 IMU/触摸板子设备时的重试间隔
 
 pub struct GlobalGamepadState {
     pub gamepad_state: Arc<GamepadState>,
     /// 当值为 true 时，表示更新任务正在运行
     pub update_running: Arc<AtomicBool>,
+    /// 下一次 start_update 时使用的采样器配置
+    pub sampler_config: Arc<RwLock<SamplerConfig>>,
+    pub sampler_stats: Arc<RwLock<SamplerStats>>,
+    /// 正在进行的映射向导
+    pub mapping_wizard: Arc<RwLock<Option<MappingWizard>>>,
+    /// 每次开始或停止震动时递增，旧的震动序列据此退出
+    pub rumble_generation: Arc<AtomicU64>,
+    /// 正在进行的摇杆校准
+    pub calibration: Arc<RwLock<Option<CalibrationSession>>>,
+}
+
+impl GlobalGamepadState {
+    /// 将持久化设置应用到运行状态（日志大小、采样器配置）
+    pub fn apply_settings(&self, settings: &Settings) {
+        self.gamepad_state.set_log_size(settings.log_size);
+        if let Ok(mut sampler_config) = self.sampler_config.write() {
+            *sampler_config = settings.sampler.clone();
+        }
+    }
 }
 
 impl Default for GlobalGamepadState {
@@ -30,6 +79,11 @@ impl Default for GlobalGamepadState {
         Self {
             gamepad_state: Arc::new(GamepadState::new()),
             update_running: Arc::new(AtomicBool::new(false)),
+            sampler_config: Arc::new(RwLock::new(SamplerConfig::default())),
+            sampler_stats: Arc::new(RwLock::new(SamplerStats::new())),
+            mapping_wizard: Arc::new(RwLock::new(None)),
+            rumble_generation: Arc::new(AtomicU64::new(0)),
+            calibration: Arc::new(RwLock::new(None)),
         }
     }
 }
@@ -42,19 +96,21 @@ pub fn stop_update(state: tauri::State<'_, GlobalGamepadState>) {
 #[tauri::command]
 pub fn get_gamepad_ids(state: tauri::State<'_, GlobalGamepadState>) -> Vec<u32> {
     let gamepad_state = &state.gamepad_state;
-    let mut ids: Vec<u32> = gamepad_state
-        .get_cur_gamepads()
-        .iter()
-        .map(|id| *id)
-        .collect();
+    let mut ids: Vec<u32> = gamepad_state.get_cur_gamepads().iter().copied().collect();
     ids.sort();
     ids
 }
 
 #[tauri::command]
-pub fn set_log_size(state: tauri::State<'_, GlobalGamepadState>, log_size: usize) {
-    let gamepad_state = &state.gamepad_state;
-    gamepad_state.set_log_size(log_size);
+pub fn set_log_size(
+    state: tauri::State<'_, GlobalGamepadState>,
+    settings_store: tauri::State<'_, SettingsStore>,
+    log_size: usize,
+) -> Result<(), GamepadError> {
+    // modify 会先校验取值，保存成功后才应用到日志
+    settings_store.modify(|settings| settings.log_size = log_size)?;
+    state.gamepad_state.set_log_size(log_size);
+    Ok(())
 }
 
 #[tauri::command]
@@ -63,6 +119,621 @@ pub fn clean_log(state: tauri::State<'_, GlobalGamepadState>) {
     gamepad_state.reset();
 }
 
+#[tauri::command]
+pub fn set_sampler_config(
+    state: tauri::State<'_, GlobalGamepadState>,
+    settings_store: tauri::State<'_, SettingsStore>,
+    config: SamplerConfig,
+) -> Result<(), GamepadError> {
+    config.validate()?;
+    settings_store.modify(|settings| settings.sampler = config.clone())?;
+    if let Ok(mut sampler_config) = state.sampler_config.write() {
+        *sampler_config = config;
+        return Ok(());
+    }
+    Err(GamepadError::lock("sampler config"))
+}
+
+#[tauri::command]
+pub fn get_sampler_config(state: tauri::State<'_, GlobalGamepadState>) -> SamplerConfig {
+    state
+        .sampler_config
+        .read()
+        .map(|config| config.clone())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn get_sampler_stats(state: tauri::State<'_, GlobalGamepadState>) -> SamplerStats {
+    state
+        .sampler_stats
+        .read()
+        .map(|stats| stats.clone())
+        .unwrap_or_else(|_| SamplerStats::new())
+}
+
+#[tauri::command]
+pub fn get_settings(settings_store: tauri::State<'_, SettingsStore>) -> Settings {
+    settings_store.get()
+}
+
+/// 启动时读取设置文件被忽略而使用默认值的字段
+#[tauri::command]
+pub fn get_rejected_settings(
+    settings_store: tauri::State<'_, SettingsStore>,
+) -> Vec<RejectedSetting> {
+    settings_store.rejected()
+}
+
+#[tauri::command]
+pub fn update_settings(
+    state: tauri::State<'_, GlobalGamepadState>,
+    settings_store: tauri::State<'_, SettingsStore>,
+    settings: Settings,
+) -> Result<Settings, GamepadError> {
+    settings_store.update(settings.clone())?;
+    state.apply_settings(&settings);
+    Ok(settings)
+}
+
+#[tauri::command]
+pub fn get_profiles(profile_store: tauri::State<'_, ProfileStore>) -> Vec<GamepadProfile> {
+    profile_store.all()
+}
+
+/// 启动时配置文件无法完整读取的情况（原文件已备份）
+#[tauri::command]
+pub fn get_profile_load_error(
+    profile_store: tauri::State<'_, ProfileStore>,
+) -> Option<ProfileLoadError> {
+    profile_store.load_error()
+}
+
+#[tauri::command]
+pub fn get_profile(
+    profile_store: tauri::State<'_, ProfileStore>,
+    key: String,
+) -> Option<GamepadProfile> {
+    profile_store.get(&key)
+}
+
+#[tauri::command]
+pub fn update_profile(
+    profile_store: tauri::State<'_, ProfileStore>,
+    profile: GamepadProfile,
+) -> Result<(), GamepadError> {
+    profile_store.update(profile)
+}
+
+#[tauri::command]
+pub fn delete_profile(
+    profile_store: tauri::State<'_, ProfileStore>,
+    key: String,
+) -> Result<(), GamepadError> {
+    profile_store.remove(&key)
+}
+
+/// 电池状态、电量历史与续航估算
+#[tauri::command]
+pub fn get_battery_report(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+) -> Result<BatteryReport, GamepadError> {
+    state.gamepad_state.get_battery_report(user_id)
+}
+
+/// IMU 回报率、噪声与零漂统计；设备没有体感传感器或未记录时为 None
+#[tauri::command]
+pub fn get_motion_stats(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+) -> Option<MotionStats> {
+    state
+        .gamepad_state
+        .get_motion_data(user_id)
+        .map(|(_, stats)| stats)
+}
+
+/// 触摸板回报率与覆盖率；设备没有触摸板或未记录时为 None
+#[tauri::command]
+pub fn get_touchpad_stats(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+) -> Option<TouchpadStats> {
+    state
+        .gamepad_state
+        .get_touchpad_data(user_id)
+        .map(|(_, stats)| stats)
+}
+
+/// 摇杆松开回中的过冲、振荡次数与稳定时间；未记录时为 None
+#[tauri::command]
+pub fn get_snapback_analysis(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+) -> Option<StickSnapback> {
+    state.gamepad_state.get_snapback(user_id)
+}
+
+/// 基于回报率日志的轴间串扰（同一摇杆 X/Y 之间、左右摇杆之间）
+#[tauri::command]
+pub fn get_coupling_analysis(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+) -> CouplingReport {
+    CouplingReport::analyze(&state.gamepad_state.axis_samples_since(user_id, 0))
+}
+
+/// 按设置中的阈值评估回报率稳定性、圆度、漂移、噪声、死区与按键抖动
+#[tauri::command]
+pub fn get_health_report(
+    state: tauri::State<'_, GlobalGamepadState>,
+    settings_store: tauri::State<'_, SettingsStore>,
+    user_id: u32,
+) -> Result<HealthReport, GamepadError> {
+    health_report(&state, &settings_store, user_id)
+}
+
+/// 生成健康报告并以 JSON 写入 path
+#[tauri::command]
+pub fn export_health_report(
+    state: tauri::State<'_, GlobalGamepadState>,
+    settings_store: tauri::State<'_, SettingsStore>,
+    user_id: u32,
+    path: String,
+) -> Result<HealthReport, GamepadError> {
+    let report = health_report(&state, &settings_store, user_id)?;
+    let content = serde_json::to_string_pretty(&report)?;
+    std::fs::write(&path, content)?;
+    Ok(report)
+}
+
+fn health_report(
+    state: &GlobalGamepadState,
+    settings_store: &SettingsStore,
+    user_id: u32,
+) -> Result<HealthReport, GamepadError> {
+    let gamepad = state.gamepad_state.get_xinput_gamepad(user_id)?;
+    let data = state
+        .gamepad_state
+        .get_health_data(user_id)
+        .ok_or_else(|| GamepadError::invalid("No data has been recorded for this gamepad"))?;
+    Ok(HealthReport::evaluate(
+        &gamepad,
+        data,
+        &settings_store.get().health,
+    ))
+}
+
+/// 当前设备实际使用的映射（标准布局、内置映射或用户映射）
+#[tauri::command]
+pub fn get_applied_mapping(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+) -> Result<AppliedMapping, GamepadError> {
+    state.gamepad_state.get_applied_mapping(user_id)
+}
+
+#[tauri::command]
+pub fn get_user_mappings(mapping_store: tauri::State<'_, Arc<MappingStore>>) -> Vec<String> {
+    mapping_store.user_mappings()
+}
+
+#[tauri::command]
+pub fn set_user_mapping(
+    state: tauri::State<'_, GlobalGamepadState>,
+    mapping_store: tauri::State<'_, Arc<MappingStore>>,
+    mapping: String,
+) -> Result<(), GamepadError> {
+    mapping_store.set_user_mapping(&mapping)?;
+    state.gamepad_state.reload_mappings();
+    Ok(())
+}
+
+#[tauri::command]
+pub fn delete_user_mapping(
+    state: tauri::State<'_, GlobalGamepadState>,
+    mapping_store: tauri::State<'_, Arc<MappingStore>>,
+    guid: String,
+) -> Result<(), GamepadError> {
+    mapping_store.remove_user_mapping(&guid)?;
+    state.gamepad_state.reload_mappings();
+    Ok(())
+}
+
+/// 启动映射向导，后台读取原始输入推进向导，并发送 "mapping_wizard" 事件
+#[tauri::command]
+pub fn start_mapping_wizard(
+    app_handle: AppHandle,
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+) -> Result<WizardStatus, GamepadError> {
+    let gamepad_state = Arc::clone(&state.gamepad_state);
+    let gamepad = gamepad_state.get_xinput_gamepad(user_id)?;
+    gamepad_state.read_raw_joystick(user_id)?;
+
+    let wizard = MappingWizard::new(&gamepad.guid, &gamepad.name);
+    let started = wizard.started();
+    let status = wizard.status();
+    match state.mapping_wizard.write() {
+        Ok(mut current) => *current = Some(wizard),
+        Err(_) => return Err(GamepadError::lock("mapping wizard")),
+    }
+
+    let mapping_wizard = state.mapping_wizard.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut poll_interval = time::interval(Duration::from_millis(WIZARD_POLL_INTERVAL));
+        let mut last_step = None;
+
+        loop {
+            poll_interval.tick().await;
+            let status = {
+                let Ok(mut current) = mapping_wizard.write() else {
+                    return;
+                };
+                // 向导被取消、已完成保存或被新的向导替换
+                let Some(wizard) = current.as_mut().filter(|w| w.started() == started) else {
+                    return;
+                };
+                let Ok(raw) = gamepad_state.read_raw_joystick(user_id) else {
+                    continue;
+                };
+                wizard.update(&raw);
+                wizard.status()
+            };
+
+            // 只在阶段或步骤变化时发送
+            if last_step != Some((status.phase, status.step)) {
+                last_step = Some((status.phase, status.step));
+                let _ = app_handle.emit("mapping_wizard", status.clone());
+            }
+            if status.phase == WizardPhase::Done {
+                return;
+            }
+        }
+    });
+    Ok(status)
+}
+
+#[tauri::command]
+pub fn get_mapping_wizard_status(
+    state: tauri::State<'_, GlobalGamepadState>,
+) -> Option<WizardStatus> {
+    state
+        .mapping_wizard
+        .read()
+        .ok()
+        .and_then(|wizard| wizard.as_ref().map(MappingWizard::status))
+}
+
+/// 跳过当前询问的输入
+#[tauri::command]
+pub fn skip_mapping_step(
+    state: tauri::State<'_, GlobalGamepadState>,
+) -> Result<WizardStatus, GamepadError> {
+    let mut current = state
+        .mapping_wizard
+        .write()
+        .map_err(|_| GamepadError::lock("mapping wizard"))?;
+    let wizard = current
+        .as_mut()
+        .ok_or_else(|| GamepadError::invalid("Mapping wizard is not running"))?;
+    wizard.skip();
+    Ok(wizard.status())
+}
+
+#[tauri::command]
+pub fn cancel_mapping_wizard(state: tauri::State<'_, GlobalGamepadState>) {
+    if let Ok(mut current) = state.mapping_wizard.write() {
+        *current = None;
+    }
+}
+
+/// 保存向导生成的映射：写入用户映射并记录到设备配置，返回 SDL 映射字符串
+#[tauri::command]
+pub fn finish_mapping_wizard(
+    state: tauri::State<'_, GlobalGamepadState>,
+    mapping_store: tauri::State<'_, Arc<MappingStore>>,
+    profile_store: tauri::State<'_, ProfileStore>,
+    user_id: u32,
+) -> Result<String, GamepadError> {
+    let wizard = state
+        .mapping_wizard
+        .write()
+        .map_err(|_| GamepadError::lock("mapping wizard"))?
+        .take()
+        .ok_or_else(|| GamepadError::invalid("Mapping wizard is not running"))?;
+    let mapping = wizard.mapping();
+    if mapping.bindings.is_empty() {
+        return Err(GamepadError::invalid("No input has been mapped"));
+    }
+    let line = mapping.to_sdl_string();
+    mapping_store.set_user_mapping(&line)?;
+    state.gamepad_state.reload_mappings();
+
+    let gamepad = state.gamepad_state.get_xinput_gamepad(user_id)?;
+    let mut profile = profile_store.get_or_create(&gamepad);
+    profile.mapping = Some(line.clone());
+    profile_store.update(profile)?;
+    Ok(line)
+}
+
+/// 开始摇杆校准，读取测试中记录的摇杆样本推进校准，并发送 "calibration" 事件
+///
+/// 样本来自回报率日志，需在测试运行时进行
+#[tauri::command]
+pub fn start_calibration(
+    app_handle: AppHandle,
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+) -> Result<CalibrationStatus, GamepadError> {
+    let gamepad_state = Arc::clone(&state.gamepad_state);
+    gamepad_state.get_xinput_gamepad(user_id)?;
+    let cursor = gamepad_state
+        .axis_samples_since(user_id, 0)
+        .last()
+        .map_or(0, |(timestamp, _)| *timestamp);
+
+    let session = CalibrationSession::new(user_id, cursor);
+    let started = session.started();
+    let status = session.status();
+    match state.calibration.write() {
+        Ok(mut current) => *current = Some(session),
+        Err(_) => return Err(GamepadError::lock("calibration")),
+    }
+
+    let calibration = state.calibration.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut poll_interval = time::interval(Duration::from_millis(CALIBRATION_POLL_INTERVAL));
+        loop {
+            poll_interval.tick().await;
+            let status = {
+                let Ok(mut current) = calibration.write() else {
+                    return;
+                };
+                // 校准被取消、已保存或被新的校准替换
+                let Some(session) = current.as_mut().filter(|s| s.started() == started) else {
+                    return;
+                };
+                for (timestamp, xyxy) in gamepad_state.axis_samples_since(user_id, session.cursor())
+                {
+                    session.push(timestamp, xyxy);
+                }
+                session.status()
+            };
+            let done = status.phase == CalibrationPhase::Done;
+            let _ = app_handle.emit("calibration", status);
+            // 完成后不再有新数据，发送最终状态后结束
+            if done {
+                return;
+            }
+        }
+    });
+    Ok(status)
+}
+
+/// 当前阶段数据足够时进入下一阶段（中心 -> 全行程 -> 完成）
+#[tauri::command]
+pub fn next_calibration_step(
+    state: tauri::State<'_, GlobalGamepadState>,
+) -> Result<CalibrationStatus, GamepadError> {
+    let mut current = state
+        .calibration
+        .write()
+        .map_err(|_| GamepadError::lock("calibration"))?;
+    let session = current
+        .as_mut()
+        .ok_or_else(|| GamepadError::invalid("Calibration is not running"))?;
+    session.advance()?;
+    Ok(session.status())
+}
+
+#[tauri::command]
+pub fn cancel_calibration(state: tauri::State<'_, GlobalGamepadState>) {
+    if let Ok(mut current) = state.calibration.write() {
+        *current = None;
+    }
+}
+
+/// 保存校准结果到设备配置，apply 决定是否在输出中附带校准后的值
+#[tauri::command]
+pub fn finish_calibration(
+    state: tauri::State<'_, GlobalGamepadState>,
+    profile_store: tauri::State<'_, ProfileStore>,
+    apply: bool,
+) -> Result<HashMap<String, AxisCalibration>, GamepadError> {
+    let mut current = state
+        .calibration
+        .write()
+        .map_err(|_| GamepadError::lock("calibration"))?;
+    let session = current
+        .as_ref()
+        .ok_or_else(|| GamepadError::invalid("Calibration is not running"))?;
+    if !session.is_done() {
+        return Err(GamepadError::invalid("Calibration is not finished"));
+    }
+    let gamepad = state.gamepad_state.get_xinput_gamepad(session.user_id())?;
+    let result = session.result();
+    let mut profile = profile_store.get_or_create(&gamepad);
+    profile.calibration = result.clone();
+    profile.calibration_enabled = apply;
+    profile_store.update(profile)?;
+    *current = None;
+    Ok(result)
+}
+
+/// 保存设备的死区与响应曲线设置；transform 为空时停止输出变换后的值
+#[tauri::command]
+pub fn set_transform(
+    state: tauri::State<'_, GlobalGamepadState>,
+    profile_store: tauri::State<'_, ProfileStore>,
+    user_id: u32,
+    transform: Option<TransformSettings>,
+) -> Result<GamepadProfile, GamepadError> {
+    if let Some(transform) = &transform {
+        transform.validate()?;
+    }
+    let gamepad = state.gamepad_state.get_xinput_gamepad(user_id)?;
+    let mut profile = profile_store.get_or_create(&gamepad);
+    profile.transform_enabled = transform.is_some();
+    if let Some(transform) = transform {
+        profile.transform = transform;
+    }
+    profile_store.update(profile.clone())?;
+    Ok(profile)
+}
+
+/// 以给定强度 [0, 1] 震动 duration 毫秒
+#[tauri::command]
+pub fn play_rumble(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+    low_frequency: f64,
+    high_frequency: f64,
+    duration: u64,
+) -> Result<(), GamepadError> {
+    play_rumble_pattern(
+        state,
+        user_id,
+        RumblePattern::constant(low_frequency, high_frequency, duration),
+    )
+}
+
+/// 播放震动序列（恒定、渐变、脉冲、暂停），会打断正在播放的序列
+#[tauri::command]
+pub fn play_rumble_pattern(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+    pattern: RumblePattern,
+) -> Result<(), GamepadError> {
+    pattern.validate()?;
+    let segments = pattern.segments();
+    let gamepad_state = Arc::clone(&state.gamepad_state);
+    let rumble_generation = state.rumble_generation.clone();
+    let generation = rumble_generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+    // 先同步设置第一段，设备不支持震动时直接返回错误
+    let first = segments[0];
+    gamepad_state.set_rumble(
+        user_id,
+        first.low,
+        first.high,
+        first.duration + RUMBLE_OVERLAP,
+    )?;
+
+    thread::spawn(move || {
+        for (i, segment) in segments.iter().enumerate() {
+            if rumble_generation.load(Ordering::SeqCst) != generation {
+                return;
+            }
+            if i > 0
+                && gamepad_state
+                    .set_rumble(
+                        user_id,
+                        segment.low,
+                        segment.high,
+                        segment.duration + RUMBLE_OVERLAP,
+                    )
+                    .is_err()
+            {
+                return;
+            }
+            thread::sleep(segment.duration);
+        }
+        if rumble_generation.load(Ordering::SeqCst) == generation {
+            let _ = gamepad_state.set_rumble(user_id, 0, 0, Duration::ZERO);
+        }
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub fn stop_rumble(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+) -> Result<(), GamepadError> {
+    state.rumble_generation.fetch_add(1, Ordering::SeqCst);
+    state
+        .gamepad_state
+        .set_rumble(user_id, 0, 0, Duration::ZERO)
+}
+
+/// 设备实际具备的轴（含原生范围）、按键、扳机类型与附加功能
+#[tauri::command]
+pub fn get_device_capabilities(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+) -> Result<DeviceCapabilities, GamepadError> {
+    state.gamepad_state.get_capabilities(user_id)
+}
+
+#[tauri::command]
+pub fn get_leds(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+) -> Result<LedInfo, GamepadError> {
+    state.gamepad_state.get_leds(user_id)
+}
+
+/// 设置玩家指示灯（从 1 开始），0 熄灭
+#[tauri::command]
+pub fn set_player_led(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+    player: u8,
+) -> Result<(), GamepadError> {
+    state.gamepad_state.set_player_led(user_id, player)
+}
+
+#[tauri::command]
+pub fn set_lightbar(
+    state: tauri::State<'_, GlobalGamepadState>,
+    user_id: u32,
+    red: u8,
+    green: u8,
+    blue: u8,
+) -> Result<(), GamepadError> {
+    state.gamepad_state.set_lightbar(user_id, red, green, blue)
+}
+
+/// 用当前采样器配置测量已知回报率/抖动的虚拟设备，报告本工具自身的计时误差
+#[tauri::command]
+pub async fn calibrate_sampler(
+    state: tauri::State<'_, GlobalGamepadState>,
+    report_rate: f64,
+    jitter: u64,
+    duration: u64,
+) -> Result<SamplerCalibration, GamepadError> {
+    if !report_rate.is_finite() || report_rate <= 0.0 {
+        return Err(GamepadError::invalid(format!(
+            "Report rate must be positive, got {}",
+            report_rate
+        )));
+    }
+    if duration == 0 || duration > MAX_CALIBRATION_TIME {
+        return Err(GamepadError::invalid(format!(
+            "Calibration duration must be within 1..={} ms, got {}",
+            MAX_CALIBRATION_TIME, duration
+        )));
+    }
+    let sampler_config = state
+        .sampler_config
+        .read()
+        .map(|config| config.clone())
+        .unwrap_or_default();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        calibrate(
+            sampler_config,
+            report_rate,
+            jitter,
+            Duration::from_millis(duration),
+        )
+    })
+    .await
+    .map_err(|e| GamepadError::Io(e.to_string()))
+}
+
 #[tauri::command]
 pub fn start_update(
     app_handle: AppHandle,
@@ -76,7 +747,7 @@ pub fn start_update(
         state.update_running.store(false, Ordering::SeqCst);
         thread::sleep(Duration::from_micros(STANDBY_SLEEP_TIME));
     }
-    
+
     // 启动新的更新任务
     state.update_running.store(true, Ordering::SeqCst);
     let cancel_flag = state.update_running.clone();
@@ -84,40 +755,81 @@ pub fn start_update(
 
     let gamepad_state = Arc::clone(&state.gamepad_state);
 
-    if is_record_log {
-        let polling_gamepad_state = gamepad_state.clone();
-        let polling_cancel_flag_clone = polling_cancel_flag.clone();
+    let sampler_stats = state.sampler_stats.clone();
+    if let Ok(mut stats) = sampler_stats.write() {
+        *stats = SamplerStats::new();
+    }
 
-        thread::spawn(move || {
-            let polling_duration = Duration::from_micros(POLLING_RATE_MICROSECONDS);
-            let standby_duration = Duration::from_micros(STANDBY_SLEEP_TIME);
+    if is_record_log {
+        let sampler_config = state
+            .sampler_config
+            .read()
+            .map(|config| config.clone())
+            .unwrap_or_default();
 
-            loop {
-                if !polling_cancel_flag_clone.load(Ordering::SeqCst) {
-                    return;
-                }
+        match sampler_config.strategy {
+            #[cfg(target_os = "linux")]
+            SamplerStrategy::Event => spawn_event_sampler(
+                gamepad_state.clone(),
+                polling_cancel_flag.clone(),
+                sampler_stats.clone(),
+                sampler_config,
+                user_id,
+            ),
+            _ => spawn_polling_sampler(
+                gamepad_state.clone(),
+                polling_cancel_flag.clone(),
+                sampler_stats.clone(),
+                sampler_config,
+                user_id,
+            ),
+        }
 
-                // 高频记录游戏手柄状态
-                if let Ok(_) = polling_gamepad_state.record(user_id, true) {
-                    precise_sleep(polling_duration);
-                } else {
-                    precise_sleep(standby_duration);
-                }
-            }
-        });
+        // IMU 与触摸板独立于摇杆采样策略，总是按事件读取
+        #[cfg(target_os = "linux")]
+        {
+            spawn_motion_sampler(gamepad_state.clone(), polling_cancel_flag.clone(), user_id);
+            spawn_touchpad_sampler(gamepad_state.clone(), polling_cancel_flag.clone(), user_id);
+        }
     }
 
     // 数据发送任务
     tauri::async_runtime::spawn(async move {
         let mut emit_interval = time::interval(Duration::from_micros(1_000_000 / frame_rate));
+        let profile_store = app_handle.state::<ProfileStore>();
+        let mut profile_key = String::new();
+        let mut last_result: Option<PollingRateResult> = None;
+        let mut last_connection: Option<ConnectionStatus> = None;
+        let mut last_spectrum = time::Instant::now();
+        // 上次计算频谱时日志中最新样本的时间戳，以及计算是否仍在进行
+        let mut spectrum_cursor = 0;
+        let spectrum_busy = Arc::new(AtomicBool::new(false));
 
         loop {
             if !cancel_flag.load(Ordering::SeqCst) {
+                // 保存本次测试结果到设备配置
+                if let Some(result) = last_result {
+                    let _ = profile_store.record_result(&profile_key, result);
+                }
                 return;
             }
 
             // 获取数据并发送
-            if let Ok(gamepad) = gamepad_state.get_xinput_gamepad(user_id) {
+            if let Ok(mut gamepad) = gamepad_state.get_xinput_gamepad(user_id) {
+                last_connection = Some(ConnectionStatus::Connected);
+                // 设备变化时加载（或创建）对应配置
+                let profile = profile_store.get_or_create(&gamepad);
+                if gamepad.profile_key != profile_key {
+                    profile_key = gamepad.profile_key.clone();
+                    last_result = None;
+                    let _ = app_handle.emit("profile_applied", profile.clone());
+                }
+                profile.apply_calibration(&mut gamepad);
+                profile.apply_transform(&mut gamepad);
+                if let Some(custom_name) = profile.custom_name {
+                    gamepad.name = custom_name;
+                }
+
                 let app_clone = app_handle.clone();
 
                 // 总是发送手柄基本信息
@@ -135,9 +847,71 @@ pub fn start_update(
                         "polling_rate_log",
                         polling_rate_log_to_output_log(&polling_rate_log),
                     );
-                    let _ = app_clone.emit("polling_rate_result", polling_rate_result);
+                    let _ = app_clone.emit("polling_rate_result", polling_rate_result.clone());
+                    last_result = Some(polling_rate_result);
                     // });
                 }
+
+                if let Some(gates) = gamepad_state.get_gate_analysis(user_id) {
+                    let _ = app_handle.emit("gate_analysis", gates);
+                }
+
+                if last_spectrum.elapsed() >= SPECTRUM_INTERVAL
+                    && !spectrum_busy.load(Ordering::SeqCst)
+                {
+                    last_spectrum = time::Instant::now();
+                    // 日志没有新样本时频谱不变，不重复计算
+                    if let Some(latest) = gamepad_state
+                        .latest_log_timestamp(user_id)
+                        .filter(|timestamp| *timestamp != spectrum_cursor)
+                    {
+                        spectrum_cursor = latest;
+                        spectrum_busy.store(true, Ordering::SeqCst);
+                        let gamepad_state = gamepad_state.clone();
+                        let app_clone = app_handle.clone();
+                        let busy = spectrum_busy.clone();
+                        // 复制日志与 FFT 较耗时，放到阻塞线程池，避免占用异步运行时
+                        tauri::async_runtime::spawn_blocking(move || {
+                            let samples = gamepad_state.axis_samples_since(user_id, 0);
+                            if let Some(spectrum) = Spectrum::analyze(&samples) {
+                                let _ = app_clone.emit("spectrum", spectrum);
+                            }
+                            busy.store(false, Ordering::SeqCst);
+                        });
+                    }
+                }
+
+                if let Some((motion_log, motion_stats)) = gamepad_state.get_motion_data(user_id) {
+                    if !motion_log.is_empty() {
+                        let _ = app_handle.emit("motion_log", motion_log);
+                        let _ = app_handle.emit("motion_stats", motion_stats);
+                    }
+                }
+
+                if let Some((touchpad_log, touchpad_stats)) =
+                    gamepad_state.get_touchpad_data(user_id)
+                {
+                    if !touchpad_log.is_empty() {
+                        let _ = app_handle.emit("touchpad_log", touchpad_log);
+                        let _ = app_handle.emit("touchpad_stats", touchpad_stats);
+                    }
+                }
+
+                if is_record_log {
+                    if let Ok(stats) = sampler_stats.read() {
+                        let _ = app_handle.emit("sampler_stats", stats.clone());
+                    }
+                }
+            } else {
+                // 断开或出错时只在状态变化时通知一次，不再发送全零数据
+                let connection = gamepad_state.get_connection(user_id);
+                if last_connection != Some(connection) {
+                    last_connection = Some(connection);
+                    let _ = app_handle.emit(
+                        "gamepads_info",
+                        GamepadInfo::unavailable(user_id, connection),
+                    );
+                }
             }
 
             emit_interval.tick().await;
@@ -145,18 +919,155 @@ pub fn start_update(
     });
 }
 
-// 高精度睡眠函数，在Windows上使用更精准的计时
-fn precise_sleep(duration: Duration) {
-    #[cfg(target_os = "windows")]
-    {
-        let start = Instant::now();
-        while start.elapsed() < duration {
-            // 使用yield来避免忙等待
-            thread::yield_now();
+// 定时轮询采样：每个采样周期读取一次状态快照
+fn spawn_polling_sampler(
+    gamepad_state: Arc<GamepadState>,
+    cancel_flag: Arc<AtomicBool>,
+    sampler_stats: Arc<RwLock<SamplerStats>>,
+    sampler_config: SamplerConfig,
+    user_id: u32,
+) {
+    thread::spawn(move || {
+        let standby_duration = Duration::from_micros(STANDBY_SLEEP_TIME);
+        let mut sampler = Sampler::new(sampler_config);
+        let mut count: u64 = 0;
+
+        loop {
+            if !cancel_flag.load(Ordering::SeqCst) {
+                return;
+            }
+
+            // 高频记录游戏手柄状态
+            if gamepad_state.record(user_id, true).is_ok() {
+                sampler.wait();
+            } else {
+                thread::sleep(standby_duration);
+                sampler.resync();
+            }
+
+            count += 1;
+            if count.is_multiple_of(SAMPLER_STATS_INTERVAL) {
+                let stats = sampler.stats();
+                gamepad_state
+                    .set_sample_source(user_id, SampleSource::Polling(stats.achieved_rate));
+                if let Ok(mut sampler_stats) = sampler_stats.write() {
+                    *sampler_stats = stats;
+                }
+            }
+        }
+    });
+}
+
+// 事件驱动采样：阻塞读取设备上报，按内核时间戳记录
+#[cfg(target_os = "linux")]
+fn spawn_event_sampler(
+    gamepad_state: Arc<GamepadState>,
+    cancel_flag: Arc<AtomicBool>,
+    sampler_stats: Arc<RwLock<SamplerStats>>,
+    sampler_config: SamplerConfig,
+    user_id: u32,
+) {
+    thread::spawn(move || {
+        let standby_duration = Duration::from_micros(STANDBY_SLEEP_TIME);
+        let read_timeout = Duration::from_millis(EVENT_READ_TIMEOUT);
+        let mut sampler = Sampler::new(sampler_config);
+        let mut count: u64 = 0;
+
+        while cancel_flag.load(Ordering::SeqCst) {
+            let stream = gamepad_state.open_report_stream(user_id);
+            let mut stream = match gamepad_state.track_connection(user_id, stream) {
+                Ok(stream) => stream,
+                Err(_) => {
+                    thread::sleep(standby_duration);
+                    continue;
+                }
+            };
+            sampler.resync();
+            gamepad_state.set_sample_source(user_id, SampleSource::Event);
+
+            while cancel_flag.load(Ordering::SeqCst) {
+                // 读取失败（设备断开）时重新打开
+                let reports = stream.next_reports(read_timeout);
+                let Ok(reports) = gamepad_state.track_connection(user_id, reports) else {
+                    break;
+                };
+                for report in reports {
+                    let _ = gamepad_state.record_report(
+                        user_id,
+                        report.timestamp,
+                        report.state.thumbs,
+                        &report.state.pressed,
+                        true,
+                    );
+                    sampler.record_report(report.timestamp);
+
+                    count += 1;
+                    if count.is_multiple_of(SAMPLER_STATS_INTERVAL) {
+                        if let Ok(mut stats) = sampler_stats.write() {
+                            *stats = sampler.stats();
+                        }
+                    }
+                }
+            }
         }
-    }
-    #[cfg(not(target_os = "windows"))]
-    {
-        thread::sleep(duration);
-    }
+    });
+}
+
+// IMU 采样：阻塞读取体感子设备的上报
+#[cfg(target_os = "linux")]
+fn spawn_motion_sampler(
+    gamepad_state: Arc<GamepadState>,
+    cancel_flag: Arc<AtomicBool>,
+    user_id: u32,
+) {
+    thread::spawn(move || {
+        let retry_duration = Duration::from_millis(SUB_DEVICE_RETRY_INTERVAL);
+        let read_timeout = Duration::from_millis(EVENT_READ_TIMEOUT);
+
+        while cancel_flag.load(Ordering::SeqCst) {
+            // 连接状态以手柄主设备为准，这里只负责重试
+            let Ok(mut stream) = gamepad_state.open_motion_stream(user_id) else {
+                thread::sleep(retry_duration);
+                continue;
+            };
+
+            while cancel_flag.load(Ordering::SeqCst) {
+                let Ok(reports) = stream.next_reports(read_timeout) else {
+                    break;
+                };
+                if !reports.is_empty() {
+                    let _ = gamepad_state.record_motion(user_id, &reports);
+                }
+            }
+        }
+    });
+}
+
+// 触摸板采样：阻塞读取触摸板子设备的上报
+#[cfg(target_os = "linux")]
+fn spawn_touchpad_sampler(
+    gamepad_state: Arc<GamepadState>,
+    cancel_flag: Arc<AtomicBool>,
+    user_id: u32,
+) {
+    thread::spawn(move || {
+        let retry_duration = Duration::from_millis(SUB_DEVICE_RETRY_INTERVAL);
+        let read_timeout = Duration::from_millis(EVENT_READ_TIMEOUT);
+
+        while cancel_flag.load(Ordering::SeqCst) {
+            let Ok(mut stream) = gamepad_state.open_touchpad_stream(user_id) else {
+                thread::sleep(retry_duration);
+                continue;
+            };
+
+            while cancel_flag.load(Ordering::SeqCst) {
+                let Ok(frames) = stream.next_frames(read_timeout) else {
+                    break;
+                };
+                if !frames.is_empty() {
+                    let _ = gamepad_state.record_touchpad(user_id, &frames);
+                }
+            }
+        }
+    });
 }
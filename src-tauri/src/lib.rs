@@ -1,11 +1,40 @@
 use cmds::GlobalGamepadState;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use tauri::Manager;
 use util::gamepad_util::GamepadState;
+use util::mapping::{MappingStore, MAPPINGS_FILE};
+use util::profile::{ProfileStore, PROFILES_FILE};
+use util::settings::{SettingsStore, SETTINGS_FILE};
 mod util {
+    pub mod calibration;
+    pub mod capabilities;
+    pub mod chatter;
+    pub mod coupling;
+    pub mod error;
+    #[cfg(target_os = "linux")]
+    pub mod evdev_input;
     pub mod gamepad_util;
+    pub mod gate;
+    pub mod health;
     pub mod input_wrapper;
+    pub mod led;
+    pub mod mapping;
+    pub mod mapping_wizard;
     pub mod math_util;
+    pub mod motion;
+    pub mod normalize;
+    pub mod power;
+    pub mod profile;
+    pub mod rumble;
+    pub mod sampler;
+    pub mod sdl_guid;
+    pub mod settings;
+    pub mod snapback;
+    pub mod spectrum;
+    pub mod touchpad;
+    pub mod transform;
+    pub mod virtual_input;
 }
 mod cmds;
 
@@ -20,9 +49,67 @@ pub fn run() {
             cmds::get_gamepad_ids,
             cmds::set_log_size,
             cmds::clean_log,
+            cmds::set_sampler_config,
+            cmds::get_sampler_config,
+            cmds::get_sampler_stats,
+            cmds::calibrate_sampler,
+            cmds::start_calibration,
+            cmds::next_calibration_step,
+            cmds::cancel_calibration,
+            cmds::finish_calibration,
+            cmds::set_transform,
+            cmds::play_rumble,
+            cmds::play_rumble_pattern,
+            cmds::stop_rumble,
+            cmds::get_device_capabilities,
+            cmds::get_leds,
+            cmds::set_player_led,
+            cmds::set_lightbar,
+            cmds::get_settings,
+            cmds::get_rejected_settings,
+            cmds::update_settings,
+            cmds::get_profiles,
+            cmds::get_profile_load_error,
+            cmds::get_profile,
+            cmds::update_profile,
+            cmds::delete_profile,
+            cmds::get_battery_report,
+            cmds::get_motion_stats,
+            cmds::get_touchpad_stats,
+            cmds::get_snapback_analysis,
+            cmds::get_coupling_analysis,
+            cmds::get_health_report,
+            cmds::export_health_report,
+            cmds::get_applied_mapping,
+            cmds::get_user_mappings,
+            cmds::set_user_mapping,
+            cmds::delete_user_mapping,
+            cmds::start_mapping_wizard,
+            cmds::get_mapping_wizard_status,
+            cmds::skip_mapping_step,
+            cmds::cancel_mapping_wizard,
+            cmds::finish_mapping_wizard,
         ])
         .setup(|app| {
-            let _ = app.handle().clone();
+            // 读取设置文件并应用到全局状态
+            let config_dir = app.path().app_config_dir().ok();
+            let settings_store =
+                SettingsStore::load(config_dir.as_ref().map(|dir| dir.join(SETTINGS_FILE)));
+            app.state::<GlobalGamepadState>()
+                .apply_settings(&settings_store.get());
+            app.manage(settings_store);
+            // 按设备身份保存的手柄配置
+            app.manage(ProfileStore::load(
+                config_dir.as_ref().map(|dir| dir.join(PROFILES_FILE)),
+            ));
+            // 内置与用户的 GameControllerDB 映射
+            let mapping_store = Arc::new(MappingStore::load(
+                config_dir.as_ref().map(|dir| dir.join(MAPPINGS_FILE)),
+            ));
+            app.state::<GlobalGamepadState>()
+                .gamepad_state
+                .set_mapping_store(mapping_store.clone());
+            app.manage(mapping_store);
             Ok(())
         })
         .build(tauri::generate_context!())
@@ -0,0 +1,317 @@
+use crate::util::error::GamepadError;
+use crate::util::input_wrapper::Axes;
+use crate::util::profile::AxisCalibration;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Instant;
+
+// 顺序与回报率日志的 xyxy 一致
+const AXES: [Axes; 4] = [
+    Axes::LeftThumbX,
+    Axes::LeftThumbY,
+    Axes::RightThumbX,
+    Axes::RightThumbY,
+];
+const MIN_CENTRE_SAMPLES: usize = 200;
+const MAX_CENTRE_SPREAD: f64 = 0.05; // 静止时各轴的最大波动，超过说明摇杆被碰到
+const MIN_SWEEP_REACH: f64 = 0.5; // 每个方向至少推到该位置才计算缩放
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CalibrationPhase {
+    /// 松开摇杆，记录静止中心
+    Centre,
+    /// 转动摇杆推满各个方向，记录行程
+    Range,
+    Done,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AxisProgress {
+    pub axis: String,
+    pub centre: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+/// 发送给前端的校准状态
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CalibrationStatus {
+    pub phase: CalibrationPhase,
+    pub centre_samples: usize,
+    pub range_samples: usize,
+    pub axes: Vec<AxisProgress>,
+    /// 当前阶段已采集足够数据，可以进入下一阶段
+    pub ready: bool,
+    /// 按当前数据计算的校准结果
+    pub result: HashMap<String, AxisCalibration>,
+}
+
+/// 基于回报率日志中归一化摇杆值的校准流程：先记录中心，再记录全行程
+#[derive(Debug, Clone)]
+pub struct CalibrationSession {
+    user_id: u32,
+    started: Instant,
+    phase: CalibrationPhase,
+    /// 已处理的最新样本时间戳 (us)
+    cursor: u64,
+    centre_sum: [f64; 4],
+    centre_min: [f64; 4],
+    centre_max: [f64; 4],
+    centre_count: usize,
+    min: [f64; 4],
+    max: [f64; 4],
+    range_count: usize,
+}
+
+impl CalibrationSession {
+    /// cursor 为开始时日志中最新样本的时间戳，之前的样本不参与校准
+    pub fn new(user_id: u32, cursor: u64) -> Self {
+        CalibrationSession {
+            user_id,
+            started: Instant::now(),
+            phase: CalibrationPhase::Centre,
+            cursor,
+            centre_sum: [0.0; 4],
+            centre_min: [f64::MAX; 4],
+            centre_max: [f64::MIN; 4],
+            centre_count: 0,
+            min: [0.0; 4],
+            max: [0.0; 4],
+            range_count: 0,
+        }
+    }
+
+    pub fn user_id(&self) -> u32 {
+        self.user_id
+    }
+
+    pub fn started(&self) -> Instant {
+        self.started
+    }
+
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+
+    pub fn push(&mut self, timestamp: u64, xyxy: [f64; 4]) {
+        if timestamp <= self.cursor {
+            return;
+        }
+        self.cursor = timestamp;
+        match self.phase {
+            CalibrationPhase::Centre => {
+                let spread = (0..4).any(|i| {
+                    xyxy[i].max(self.centre_max[i]) - xyxy[i].min(self.centre_min[i])
+                        > MAX_CENTRE_SPREAD
+                });
+                // 摇杆被碰到时重新开始记录
+                if spread {
+                    self.reset_centre();
+                }
+                for (i, v) in xyxy.iter().enumerate() {
+                    self.centre_sum[i] += v;
+                    self.centre_min[i] = self.centre_min[i].min(*v);
+                    self.centre_max[i] = self.centre_max[i].max(*v);
+                }
+                self.centre_count += 1;
+            }
+            CalibrationPhase::Range => {
+                for (i, v) in xyxy.iter().enumerate() {
+                    self.min[i] = self.min[i].min(*v);
+                    self.max[i] = self.max[i].max(*v);
+                }
+                self.range_count += 1;
+            }
+            CalibrationPhase::Done => {}
+        }
+    }
+
+    fn reset_centre(&mut self) {
+        self.centre_sum = [0.0; 4];
+        self.centre_min = [f64::MAX; 4];
+        self.centre_max = [f64::MIN; 4];
+        self.centre_count = 0;
+    }
+
+    fn centre(&self) -> [f64; 4] {
+        let n = self.centre_count.max(1) as f64;
+        self.centre_sum.map(|sum| sum / n)
+    }
+
+    fn is_ready(&self) -> bool {
+        match self.phase {
+            CalibrationPhase::Centre => self.centre_count >= MIN_CENTRE_SAMPLES,
+            CalibrationPhase::Range => {
+                let centre = self.centre();
+                (0..4).all(|i| {
+                    self.max[i] - centre[i] >= MIN_SWEEP_REACH
+                        && centre[i] - self.min[i] >= MIN_SWEEP_REACH
+                })
+            }
+            CalibrationPhase::Done => true,
+        }
+    }
+
+    /// 进入下一阶段；当前阶段数据不足时返回错误
+    pub fn advance(&mut self) -> Result<(), GamepadError> {
+        if !self.is_ready() {
+            return Err(GamepadError::invalid(match self.phase {
+                CalibrationPhase::Centre => "Keep the sticks at rest until the centre is recorded",
+                _ => "Rotate both sticks to their full range in every direction",
+            }));
+        }
+        match self.phase {
+            CalibrationPhase::Centre => {
+                let centre = self.centre();
+                self.min = centre;
+                self.max = centre;
+                self.phase = CalibrationPhase::Range;
+            }
+            CalibrationPhase::Range => self.phase = CalibrationPhase::Done,
+            CalibrationPhase::Done => {}
+        }
+        Ok(())
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.phase == CalibrationPhase::Done
+    }
+
+    /// 偏移为静止中心；缩放取两侧中较短的行程，使两侧都能推满 1
+    pub fn result(&self) -> HashMap<String, AxisCalibration> {
+        if self.centre_count == 0 {
+            return HashMap::new();
+        }
+        let centre = self.centre();
+        AXES.iter()
+            .enumerate()
+            .map(|(i, axis)| {
+                let reach = (self.max[i] - centre[i]).min(centre[i] - self.min[i]);
+                let scale = if self.phase != CalibrationPhase::Centre && reach >= MIN_SWEEP_REACH {
+                    1.0 / reach
+                } else {
+                    1.0
+                };
+                (
+                    axis.to_string(),
+                    AxisCalibration {
+                        offset: centre[i],
+                        scale,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    pub fn status(&self) -> CalibrationStatus {
+        let centre = self.centre();
+        CalibrationStatus {
+            phase: self.phase,
+            centre_samples: self.centre_count,
+            range_samples: self.range_count,
+            axes: AXES
+                .iter()
+                .enumerate()
+                .map(|(i, axis)| AxisProgress {
+                    axis: axis.to_string(),
+                    centre: centre[i],
+                    min: self.min[i],
+                    max: self.max[i],
+                })
+                .collect(),
+            ready: self.is_ready(),
+            result: self.result(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push_centre(session: &mut CalibrationSession, from: u64, count: u64, xyxy: [f64; 4]) {
+        for t in from..from + count {
+            session.push(t, xyxy);
+        }
+    }
+
+    #[test]
+    fn samples_before_cursor_ignored() {
+        let mut session = CalibrationSession::new(0, 100);
+        push_centre(&mut session, 1, 100, [0.5; 4]);
+        assert_eq!(session.status().centre_samples, 0);
+        assert_eq!(session.cursor(), 100);
+    }
+
+    #[test]
+    fn centre_spread_resets() {
+        let mut session = CalibrationSession::new(0, 0);
+        push_centre(&mut session, 1, 50, [0.0; 4]);
+        assert_eq!(session.status().centre_samples, 50);
+        // 超过允许波动时只保留碰到之后的样本
+        session.push(51, [0.2, 0.0, 0.0, 0.0]);
+        assert_eq!(session.status().centre_samples, 1);
+        // 波动范围内不会重置
+        session.push(52, [0.2 + MAX_CENTRE_SPREAD / 2.0, 0.0, 0.0, 0.0]);
+        assert_eq!(session.status().centre_samples, 2);
+    }
+
+    #[test]
+    fn centre_ready_after_enough_samples() {
+        let mut session = CalibrationSession::new(0, 0);
+        push_centre(&mut session, 1, MIN_CENTRE_SAMPLES as u64 - 1, [0.0; 4]);
+        assert!(!session.is_ready());
+        assert!(session.advance().is_err());
+        session.push(MIN_CENTRE_SAMPLES as u64, [0.0; 4]);
+        assert!(session.is_ready());
+        assert!(session.advance().is_ok());
+        assert_eq!(session.status().phase, CalibrationPhase::Range);
+    }
+
+    #[test]
+    fn range_ready_needs_every_direction() {
+        let mut session = CalibrationSession::new(0, 0);
+        push_centre(&mut session, 1, MIN_CENTRE_SAMPLES as u64, [0.0; 4]);
+        session.advance().unwrap();
+        let start = MIN_CENTRE_SAMPLES as u64 + 1;
+        session.push(start, [1.0; 4]);
+        assert!(!session.is_ready());
+        session.push(start + 1, [-1.0, -1.0, -1.0, -0.4]);
+        assert!(!session.is_ready());
+        session.push(start + 2, [-1.0; 4]);
+        assert!(session.is_ready());
+        session.advance().unwrap();
+        assert!(session.is_done());
+    }
+
+    #[test]
+    fn result_offset_and_scale() {
+        let mut session = CalibrationSession::new(0, 0);
+        assert!(session.result().is_empty());
+        let centre = [0.1, -0.05, 0.0, 0.02];
+        push_centre(&mut session, 1, MIN_CENTRE_SAMPLES as u64, centre);
+
+        // 中心阶段只有偏移，缩放保持 1
+        let result = session.result();
+        let lx = &result[&Axes::LeftThumbX.to_string()];
+        assert!((lx.offset - 0.1).abs() < 1e-9);
+        assert_eq!(lx.scale, 1.0);
+
+        session.advance().unwrap();
+        let start = MIN_CENTRE_SAMPLES as u64 + 1;
+        session.push(start, [0.9, 0.95, 1.0, 1.0]);
+        session.push(start + 1, [-1.0, -1.0, -0.8, -1.0]);
+        let result = session.result();
+        // 取两侧中较短的行程
+        let lx = &result[&Axes::LeftThumbX.to_string()];
+        assert!((lx.scale - 1.0 / 0.8).abs() < 1e-9);
+        let ly = &result[&Axes::LeftThumbY.to_string()];
+        assert!((ly.offset + 0.05).abs() < 1e-9);
+        assert!((ly.scale - 1.0 / 0.95).abs() < 1e-9);
+        let rx = &result[&Axes::RightThumbX.to_string()];
+        assert!((rx.scale - 1.0 / 0.8).abs() < 1e-9);
+        // 校准后推满的一侧正好为 1
+        assert!((lx.apply(0.9) - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::util::led::LedInfo;
+use serde::{Deserialize, Serialize};
+
+/// 扳机类型：模拟量、只有按下/松开，或者没有扳机
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerType {
+    Analog,
+    Digital,
+    None,
+}
+
+/// 单个逻辑轴（摇杆轴或模拟扳机）对应的原生轴
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AxisCapability {
+    /// 与 GamepadInfo 中 axes/buttons 的键一致，扳机为 LeftTrigger/RightTrigger
+    pub name: String,
+    /// 原生轴名称（如 ABS_X），XInput 没有
+    pub source: Option<String>,
+    /// 原生取值范围
+    pub min: i32,
+    pub max: i32,
+    /// 驱动声明的中心死区与抖动过滤量
+    pub flat: i32,
+    pub fuzz: i32,
+    /// 每毫米的单位数，摇杆通常为 0（未知）
+    pub resolution: i32,
+}
+
+impl AxisCapability {
+    pub fn new(name: impl Into<String>, min: i32, max: i32) -> Self {
+        AxisCapability {
+            name: name.into(),
+            source: None,
+            min,
+            max,
+            flat: 0,
+            fuzz: 0,
+            resolution: 0,
+        }
+    }
+}
+
+/// 设备实际具备的输入与输出，前端与分析据此调整
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DeviceCapabilities {
+    pub axes: Vec<AxisCapability>,
+    /// 设备具备的按键，名称与 GamepadInfo.buttons 的键一致（不含扳机）
+    pub buttons: Vec<String>,
+    pub left_trigger: TriggerType,
+    pub right_trigger: TriggerType,
+    pub rumble: bool,
+    pub motion: bool,
+    pub touchpad: bool,
+    pub battery: bool,
+    pub leds: LedInfo,
+}
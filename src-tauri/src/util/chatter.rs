@@ -0,0 +1,175 @@
+use crate::util::input_wrapper::Buttons;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+const CHATTER_WINDOW: u64 = 15_000; // us, 人手无法在该间隔内连续按下/松开
+
+/// 单个按键的按下次数与抖动（连击）次数
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ButtonChatter {
+    pub button: String,
+    pub presses: u32,
+    /// 与上一次状态变化间隔小于 15ms 的变化次数
+    pub chatter: u32,
+    /// 相邻两次状态变化的最短间隔 (ms)
+    pub min_interval: Option<f64>,
+}
+
+impl ButtonChatter {
+    /// 每次按下平均出现的抖动次数
+    pub fn ratio(&self) -> f64 {
+        if self.presses == 0 {
+            0.0
+        } else {
+            self.chatter as f64 / self.presses as f64
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ButtonTrack {
+    pressed: bool,
+    last_change: u64,
+    presses: u32,
+    chatter: u32,
+    min_interval: Option<u64>,
+}
+
+/// 按采样线程的时间戳记录按键状态变化，用于检测按键抖动
+#[derive(Debug, Clone, Default)]
+pub struct ButtonLog {
+    tracks: HashMap<Buttons, ButtonTrack>,
+}
+
+impl ButtonLog {
+    pub fn new() -> Self {
+        ButtonLog::default()
+    }
+
+    pub fn record(&mut self, timestamp: u64, pressed: &HashSet<Buttons>) {
+        for button in pressed {
+            if !self.tracks.get(button).is_some_and(|track| track.pressed) {
+                self.change(button, timestamp, true);
+            }
+        }
+        let released: Vec<Buttons> = self
+            .tracks
+            .iter()
+            .filter(|(button, track)| track.pressed && !pressed.contains(*button))
+            .map(|(button, _)| button.clone())
+            .collect();
+        for button in released {
+            self.change(&button, timestamp, false);
+        }
+    }
+
+    fn change(&mut self, button: &Buttons, timestamp: u64, pressed: bool) {
+        match self.tracks.get_mut(button) {
+            Some(track) => {
+                let interval = timestamp.saturating_sub(track.last_change);
+                if interval < CHATTER_WINDOW {
+                    track.chatter += 1;
+                }
+                track.min_interval =
+                    Some(track.min_interval.map_or(interval, |min| min.min(interval)));
+                track.pressed = pressed;
+                track.last_change = timestamp;
+                if pressed {
+                    track.presses += 1;
+                }
+            }
+            None => {
+                self.tracks.insert(
+                    button.clone(),
+                    ButtonTrack {
+                        pressed,
+                        last_change: timestamp,
+                        presses: pressed as u32,
+                        chatter: 0,
+                        min_interval: None,
+                    },
+                );
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.tracks.clear();
+    }
+
+    pub fn stats(&self) -> Vec<ButtonChatter> {
+        let mut stats: Vec<ButtonChatter> = self
+            .tracks
+            .iter()
+            .map(|(button, track)| ButtonChatter {
+                button: button.to_string(),
+                presses: track.presses,
+                chatter: track.chatter,
+                min_interval: track.min_interval.map(|interval| interval as f64 / 1000.0),
+            })
+            .collect();
+        stats.sort_by(|a, b| a.button.cmp(&b.button));
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pressed(buttons: &[Buttons]) -> HashSet<Buttons> {
+        buttons.iter().cloned().collect()
+    }
+
+    #[test]
+    fn counts_presses_and_chatter() {
+        let mut log = ButtonLog::new();
+        log.record(0, &pressed(&[Buttons::A]));
+        log.record(100_000, &pressed(&[Buttons::B]));
+        // 松开 5 ms 后再次按下，计为一次抖动
+        log.record(105_000, &pressed(&[Buttons::A, Buttons::B]));
+        log.record(200_000, &pressed(&[Buttons::B]));
+        log.record(300_000, &pressed(&[Buttons::A, Buttons::B]));
+
+        let stats = log.stats();
+        assert_eq!(stats.len(), 2);
+        let a = &stats[0];
+        assert_eq!(a.button, Buttons::A.to_string());
+        assert_eq!(a.presses, 3);
+        assert_eq!(a.chatter, 1);
+        assert_eq!(a.min_interval, Some(5.0));
+        assert!((a.ratio() - 1.0 / 3.0).abs() < 1e-9);
+
+        // 一直按住的按键没有抖动
+        let b = &stats[1];
+        assert_eq!(b.presses, 1);
+        assert_eq!(b.chatter, 0);
+        assert_eq!(b.min_interval, None);
+    }
+
+    #[test]
+    fn window_boundary_is_not_chatter() {
+        let mut log = ButtonLog::new();
+        log.record(0, &pressed(&[Buttons::A]));
+        log.record(CHATTER_WINDOW, &pressed(&[]));
+        log.record(CHATTER_WINDOW * 2 - 1, &pressed(&[Buttons::A]));
+        let stats = log.stats();
+        assert_eq!(stats[0].presses, 2);
+        assert_eq!(stats[0].chatter, 1);
+    }
+
+    #[test]
+    fn clear_resets_tracks() {
+        let mut log = ButtonLog::new();
+        log.record(0, &pressed(&[Buttons::A]));
+        log.clear();
+        assert!(log.stats().is_empty());
+        let unused = ButtonChatter {
+            button: "A".to_string(),
+            presses: 0,
+            chatter: 0,
+            min_interval: None,
+        };
+        assert_eq!(unused.ratio(), 0.0);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::util::input_wrapper::Axes;
+use serde::{Deserialize, Serialize};
+
+// 顺序与回报率日志的 xyxy 一致
+const AXES: [Axes; 4] = [
+    Axes::LeftThumbX,
+    Axes::LeftThumbY,
+    Axes::RightThumbX,
+    Axes::RightThumbY,
+];
+const REST_BAND: f64 = 0.15; // 被干扰轴在该范围内才认为没有被主动推动
+const MIN_SOURCE_SPAN: f64 = 0.5; // 干扰源轴的行程不足时无法判断
+const MIN_SAMPLES: usize = 100;
+const COUPLED_CORRELATION: f64 = 0.5; // 相关系数与斜率都超过阈值才判定为串扰
+const COUPLED_SLOPE: f64 = 0.01;
+
+/// source 轴移动时 target 轴的跟随程度
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AxisCoupling {
+    pub source: String,
+    pub target: String,
+    /// target 静止时两轴的皮尔逊相关系数
+    pub correlation: f64,
+    /// 耦合系数：source 每移动 1，target 变化的量（线性回归斜率）
+    pub slope: f64,
+    /// 参与计算的样本数
+    pub samples: usize,
+    /// source 的行程与样本数足够时才有结论
+    pub valid: bool,
+    pub coupled: bool,
+}
+
+/// 各轴两两之间的串扰，包括同一摇杆的 X/Y 与左右摇杆之间
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CouplingReport {
+    pub pairs: Vec<AxisCoupling>,
+    /// 有效结果中耦合系数绝对值的最大值
+    pub max_slope: f64,
+}
+
+impl CouplingReport {
+    /// samples 为回报率日志中归一化后的 xyxy
+    pub fn analyze(samples: &[(u64, [f64; 4])]) -> Self {
+        let mut pairs = Vec::with_capacity(AXES.len() * (AXES.len() - 1));
+        for source in 0..AXES.len() {
+            for target in (0..AXES.len()).filter(|target| *target != source) {
+                pairs.push(couple(samples, source, target));
+            }
+        }
+        let max_slope = pairs
+            .iter()
+            .filter(|pair| pair.valid)
+            .map(|pair| pair.slope.abs())
+            .fold(0.0, f64::max);
+        CouplingReport { pairs, max_slope }
+    }
+}
+
+/// 只使用 target 处于静止范围内的样本，排除用户同时推动两轴（如斜向）的情况
+fn couple(samples: &[(u64, [f64; 4])], source: usize, target: usize) -> AxisCoupling {
+    let points: Vec<(f64, f64)> = samples
+        .iter()
+        .map(|(_, xyxy)| (xyxy[source], xyxy[target]))
+        .filter(|(_, b)| b.abs() <= REST_BAND)
+        .collect();
+    let n = points.len() as f64;
+    let (min, max) = points
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(min, max), (a, _)| {
+            (min.min(*a), max.max(*a))
+        });
+    let valid = points.len() >= MIN_SAMPLES && max - min >= MIN_SOURCE_SPAN;
+
+    let (mut correlation, mut slope) = (0.0, 0.0);
+    if valid {
+        let mean_a = points.iter().map(|(a, _)| a).sum::<f64>() / n;
+        let mean_b = points.iter().map(|(_, b)| b).sum::<f64>() / n;
+        let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+        for (a, b) in &points {
+            cov += (a - mean_a) * (b - mean_b);
+            var_a += (a - mean_a).powi(2);
+            var_b += (b - mean_b).powi(2);
+        }
+        slope = cov / var_a;
+        // target 完全不变时没有串扰
+        if var_b > 0.0 {
+            correlation = cov / (var_a * var_b).sqrt();
+        }
+    }
+
+    AxisCoupling {
+        source: AXES[source].to_string(),
+        target: AXES[target].to_string(),
+        correlation,
+        slope,
+        samples: points.len(),
+        valid,
+        coupled: valid && correlation.abs() >= COUPLED_CORRELATION && slope.abs() >= COUPLED_SLOPE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(report: &CouplingReport, source: Axes, target: Axes) -> &AxisCoupling {
+        report
+            .pairs
+            .iter()
+            .find(|p| p.source == source.to_string() && p.target == target.to_string())
+            .unwrap()
+    }
+
+    #[test]
+    fn known_slope_is_detected() {
+        // 左摇杆 X 从 -1 扫到 1，Y 轴按 0.05 的比例跟随，右摇杆不动
+        let samples: Vec<(u64, [f64; 4])> = (0..=200)
+            .map(|i| {
+                let x = i as f64 / 100.0 - 1.0;
+                (i * 1000, [x, 0.05 * x, 0.0, 0.0])
+            })
+            .collect();
+        let report = CouplingReport::analyze(&samples);
+        assert_eq!(report.pairs.len(), 12);
+
+        let coupled = pair(&report, Axes::LeftThumbX, Axes::LeftThumbY);
+        assert!(coupled.valid);
+        assert!(coupled.coupled);
+        assert!((coupled.slope - 0.05).abs() < 1e-9);
+        assert!((coupled.correlation - 1.0).abs() < 1e-9);
+        assert!((report.max_slope - 0.05).abs() < 1e-9);
+
+        // 完全不变的轴没有串扰
+        let still = pair(&report, Axes::LeftThumbX, Axes::RightThumbX);
+        assert!(still.valid);
+        assert!(!still.coupled);
+        assert_eq!(still.slope, 0.0);
+
+        // 干扰源行程不足时无法判断
+        let short = pair(&report, Axes::LeftThumbY, Axes::LeftThumbX);
+        assert!(!short.valid);
+        assert!(!short.coupled);
+    }
+
+    #[test]
+    fn too_few_samples_is_invalid() {
+        let samples: Vec<(u64, [f64; 4])> = (0..10)
+            .map(|i| (i * 1000, [i as f64 / 10.0, 0.0, 0.0, 0.0]))
+            .collect();
+        let report = CouplingReport::analyze(&samples);
+        assert!(report.pairs.iter().all(|p| !p.valid));
+        assert_eq!(report.max_slope, 0.0);
+    }
+}
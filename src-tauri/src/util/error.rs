@@ -0,0 +1,85 @@
+use serde::Serialize;
+use std::fmt;
+use std::io;
+
+/// 后端统一的错误类型，序列化后交给前端区分错误种类
+///
+/// 序列化格式：`{ "kind": "device_not_connected", "message": "..." }`
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum GamepadError {
+    /// 指定槽位/设备没有连接手柄
+    DeviceNotConnected(String),
+    /// 内部锁被污染（持锁线程 panic）
+    LockPoisoned(String),
+    /// 当前平台或构建不支持该功能
+    BackendUnavailable(String),
+    /// 没有访问设备的权限（如 /dev/input/eventN）
+    PermissionDenied(String),
+    Io(String),
+    /// 参数或配置取值非法
+    InvalidArgument(String),
+    /// 文件或数据的内容无法解析
+    InvalidData(String),
+}
+
+impl GamepadError {
+    pub fn not_connected(id: u32) -> Self {
+        GamepadError::DeviceNotConnected(format!("Device ID {} is not connected", id))
+    }
+
+    pub fn lock(resource: &str) -> Self {
+        GamepadError::LockPoisoned(format!("Failed to lock {}", resource))
+    }
+
+    pub fn invalid(message: impl Into<String>) -> Self {
+        GamepadError::InvalidArgument(message.into())
+    }
+
+    /// 设备读写时的 I/O 错误：区分无权限与设备已拔出
+    pub fn from_device_io(id: u32, e: io::Error) -> Self {
+        #[cfg(target_os = "linux")]
+        if e.raw_os_error() == Some(libc::ENODEV) {
+            return GamepadError::not_connected(id);
+        }
+        match e.kind() {
+            io::ErrorKind::PermissionDenied => GamepadError::PermissionDenied(format!(
+                "No permission to access device ID {}: {}",
+                id, e
+            )),
+            io::ErrorKind::NotFound => GamepadError::not_connected(id),
+            _ => GamepadError::Io(format!("Device ID {}: {}", id, e)),
+        }
+    }
+}
+
+impl fmt::Display for GamepadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GamepadError::DeviceNotConnected(message)
+            | GamepadError::LockPoisoned(message)
+            | GamepadError::BackendUnavailable(message)
+            | GamepadError::PermissionDenied(message)
+            | GamepadError::Io(message)
+            | GamepadError::InvalidArgument(message)
+            | GamepadError::InvalidData(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for GamepadError {}
+
+impl From<io::Error> for GamepadError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::PermissionDenied => GamepadError::PermissionDenied(e.to_string()),
+            _ => GamepadError::Io(e.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for GamepadError {
+    fn from(e: serde_json::Error) -> Self {
+        GamepadError::InvalidData(e.to_string())
+    }
+}
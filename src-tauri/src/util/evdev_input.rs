@@ -0,0 +1,1407 @@
+use crate::util::capabilities::{AxisCapability, DeviceCapabilities, TriggerType};
+use crate::util::error::GamepadError;
+use crate::util::input_wrapper::{
+    Axes, Axis, Button, Buttons, Capabilities, Gamepad, Leds, RawInput, Rumble,
+};
+use crate::util::led::{LedInfo, LedSet};
+use crate::util::mapping::{
+    AppliedMapping, AxisRange, ControllerMapping, MappingSource, MappingStore, MappingTarget,
+    RawJoystick, RawSource,
+};
+use crate::util::motion::MotionSample;
+use crate::util::normalize::{denormalize_i16, NativeRange};
+use crate::util::power::{find_power_supply, read_power_supply, PowerInfo};
+use crate::util::sdl_guid::create_guid;
+use crate::util::touchpad::{TouchContact, TouchpadReport};
+use evdev::{
+    AbsoluteAxisCode, AttributeSet, Device, EventSummary, FFEffect, FFEffectCode, FFEffectData,
+    FFEffectKind, FFReplay, FFTrigger, KeyCode, MiscCode, PropType, SynchronizationCode,
+};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::fs;
+use std::os::fd::AsRawFd;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+
+const INPUT_DIR: &str = "/dev/input";
+const SYS_INPUT_DIR: &str = "/sys/class/input";
+// SDL 的按键编号从 BTN_JOYSTICK 开始，之后再回绕到 0
+const BTN_JOYSTICK: u16 = 0x120;
+// BTN_JOYSTICK 与 BTN_GAMEPAD 两个区段的结束位置
+const BTN_GAMEPAD_END: u16 = 0x140;
+const KEY_MAX: u16 = 0x2ff;
+const ABS_CNT: u16 = 0x40;
+const HAT_COUNT: u16 = 4;
+const POWER_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+// IMU 子设备的轴：ABS_X/Y/Z 为加速度，ABS_RX/RY/RZ 为角速度
+const MOTION_AXES: [AbsoluteAxisCode; 6] = [
+    AbsoluteAxisCode::ABS_X,
+    AbsoluteAxisCode::ABS_Y,
+    AbsoluteAxisCode::ABS_Z,
+    AbsoluteAxisCode::ABS_RX,
+    AbsoluteAxisCode::ABS_RY,
+    AbsoluteAxisCode::ABS_RZ,
+];
+
+const BUTTONS_MAP: [(Buttons, KeyCode); 14] = [
+    (Buttons::A, KeyCode::BTN_SOUTH),
+    (Buttons::B, KeyCode::BTN_EAST),
+    (Buttons::X, KeyCode::BTN_WEST),
+    (Buttons::Y, KeyCode::BTN_NORTH),
+    (Buttons::LeftShoulder, KeyCode::BTN_TL),
+    (Buttons::RightShoulder, KeyCode::BTN_TR),
+    (Buttons::Back, KeyCode::BTN_SELECT),
+    (Buttons::Start, KeyCode::BTN_START),
+    (Buttons::LeftThumb, KeyCode::BTN_THUMBL),
+    (Buttons::RightThumb, KeyCode::BTN_THUMBR),
+    (Buttons::DPadUp, KeyCode::BTN_DPAD_UP),
+    (Buttons::DPadDown, KeyCode::BTN_DPAD_DOWN),
+    (Buttons::DPadLeft, KeyCode::BTN_DPAD_LEFT),
+    (Buttons::DPadRight, KeyCode::BTN_DPAD_RIGHT),
+];
+
+// 仅在设备支持时显示的按键；背键按 xpad（Elite 手柄）的键码
+const EXTENDED_BUTTONS_MAP: [(Buttons, KeyCode); 7] = [
+    (Buttons::Guide, KeyCode::BTN_MODE),
+    (Buttons::Share, KeyCode::KEY_RECORD),
+    (Buttons::Misc, KeyCode::KEY_MICMUTE),
+    (Buttons::Paddle1, KeyCode::BTN_TRIGGER_HAPPY5),
+    (Buttons::Paddle2, KeyCode::BTN_TRIGGER_HAPPY6),
+    (Buttons::Paddle3, KeyCode::BTN_TRIGGER_HAPPY7),
+    (Buttons::Paddle4, KeyCode::BTN_TRIGGER_HAPPY8),
+];
+
+// 未加载映射时摇杆轴与扳机使用的原生轴
+const NATIVE_AXES: [(Axes, AbsoluteAxisCode); 4] = [
+    (Axes::LeftThumbX, AbsoluteAxisCode::ABS_X),
+    (Axes::LeftThumbY, AbsoluteAxisCode::ABS_Y),
+    (Axes::RightThumbX, AbsoluteAxisCode::ABS_RX),
+    (Axes::RightThumbY, AbsoluteAxisCode::ABS_RY),
+];
+const NATIVE_TRIGGERS: [(Buttons, AbsoluteAxisCode); 2] = [
+    (Buttons::LeftTrigger, AbsoluteAxisCode::ABS_Z),
+    (Buttons::RightTrigger, AbsoluteAxisCode::ABS_RZ),
+];
+
+/// 单个绝对轴的当前值与范围
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AbsAxis {
+    pub value: i32,
+    pub min: i32,
+    pub max: i32,
+    pub flat: i32,
+    pub fuzz: i32,
+    pub resolution: i32,
+}
+
+impl AbsAxis {
+    /// 按原生范围归一化后换算为 i16，与 AxisData.value 使用同一中心
+    fn to_i16(self) -> i16 {
+        denormalize_i16(self.range(false).normalize(self.value))
+    }
+
+    fn range(self, inverted: bool) -> NativeRange {
+        NativeRange {
+            min: self.min,
+            max: self.max,
+            flat: self.flat,
+            fuzz: self.fuzz,
+            inverted,
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        if self.max <= self.min {
+            return 0;
+        }
+        let t = (self.value - self.min) as f64 / (self.max - self.min) as f64;
+        (t * 255.0).round().clamp(0.0, 255.0) as u8
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EvdevState {
+    pub thumbs: (i16, i16, i16, i16),
+    /// 摇杆的原生值与范围，顺序同 thumbs；按映射解析时沿用源轴的原生值，
+    /// 由半轴、按键或十字键组成的摇杆为映射后的 i16
+    pub raw_thumbs: [(i32, NativeRange); 4],
+    pub triggers: (u8, u8),
+    pub pressed: HashSet<Buttons>,
+}
+
+impl EvdevState {
+    fn from_raw(abs: &HashMap<AbsoluteAxisCode, AbsAxis>, keys: &AttributeSet<KeyCode>) -> Self {
+        let axis = |code: AbsoluteAxisCode| abs.get(&code).copied().unwrap_or_default();
+        // evdev 的 Y 轴向下为正，与 XInput 相反
+        let invert = |v: i16| (-(v as i32)).clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+
+        let mut pressed: HashSet<Buttons> = BUTTONS_MAP
+            .iter()
+            .chain(EXTENDED_BUTTONS_MAP.iter())
+            .filter(|(_, key)| keys.contains(*key))
+            .map(|(btn, _)| btn.clone())
+            .collect();
+        // 部分手柄的十字键以 HAT 轴上报
+        let hat_x = axis(AbsoluteAxisCode::ABS_HAT0X).value;
+        let hat_y = axis(AbsoluteAxisCode::ABS_HAT0Y).value;
+        if hat_x < 0 {
+            pressed.insert(Buttons::DPadLeft);
+        } else if hat_x > 0 {
+            pressed.insert(Buttons::DPadRight);
+        }
+        if hat_y < 0 {
+            pressed.insert(Buttons::DPadUp);
+        } else if hat_y > 0 {
+            pressed.insert(Buttons::DPadDown);
+        }
+
+        let native = |code: AbsoluteAxisCode, inverted: bool| {
+            let info = axis(code);
+            (info.value, info.range(inverted))
+        };
+
+        EvdevState {
+            raw_thumbs: [
+                native(AbsoluteAxisCode::ABS_X, false),
+                native(AbsoluteAxisCode::ABS_Y, true),
+                native(AbsoluteAxisCode::ABS_RX, false),
+                native(AbsoluteAxisCode::ABS_RY, true),
+            ],
+            thumbs: (
+                axis(AbsoluteAxisCode::ABS_X).to_i16(),
+                invert(axis(AbsoluteAxisCode::ABS_Y).to_i16()),
+                axis(AbsoluteAxisCode::ABS_RX).to_i16(),
+                invert(axis(AbsoluteAxisCode::ABS_RY).to_i16()),
+            ),
+            triggers: (
+                axis(AbsoluteAxisCode::ABS_Z).to_u8(),
+                axis(AbsoluteAxisCode::ABS_RZ).to_u8(),
+            ),
+            pressed,
+        }
+    }
+}
+
+/// 按 SDL 的规则为设备的按键、轴、HAT 编号，映射中的 bN/aN/hN 即此编号
+#[derive(Debug, Clone, Default)]
+struct RawLayout {
+    buttons: Vec<KeyCode>,
+    axes: Vec<AbsoluteAxisCode>,
+    hats: Vec<(AbsoluteAxisCode, AbsoluteAxisCode)>,
+}
+
+impl RawLayout {
+    fn from_device(device: &Device) -> Self {
+        let mut layout = RawLayout::default();
+        if let Some(keys) = device.supported_keys() {
+            layout.buttons = (BTN_JOYSTICK..KEY_MAX)
+                .chain(0..BTN_JOYSTICK)
+                .map(KeyCode)
+                .filter(|key| keys.contains(*key))
+                .collect();
+        }
+        if let Some(axes) = device.supported_absolute_axes() {
+            let hat_start = AbsoluteAxisCode::ABS_HAT0X.0;
+            let hat_end = hat_start + HAT_COUNT * 2;
+            layout.axes = (0..ABS_CNT)
+                .filter(|code| !(hat_start..hat_end).contains(code))
+                .map(AbsoluteAxisCode)
+                .filter(|axis| axes.contains(*axis))
+                .collect();
+            layout.hats = (0..HAT_COUNT)
+                .map(|i| {
+                    (
+                        AbsoluteAxisCode(hat_start + i * 2),
+                        AbsoluteAxisCode(hat_start + i * 2 + 1),
+                    )
+                })
+                .filter(|(x, y)| axes.contains(*x) || axes.contains(*y))
+                .collect();
+        }
+        layout
+    }
+
+    fn read(
+        &self,
+        abs: &HashMap<AbsoluteAxisCode, AbsAxis>,
+        keys: &AttributeSet<KeyCode>,
+    ) -> RawJoystick {
+        let axis = |code: &AbsoluteAxisCode| abs.get(code).copied().unwrap_or_default();
+        RawJoystick {
+            buttons: self.buttons.iter().map(|key| keys.contains(*key)).collect(),
+            axes: self.axes.iter().map(|code| axis(code).to_i16()).collect(),
+            hats: self
+                .hats
+                .iter()
+                .map(|(x, y)| {
+                    let (x, y) = (axis(x).value, axis(y).value);
+                    let mut hat = 0;
+                    if y < 0 {
+                        hat |= 1;
+                    }
+                    if x > 0 {
+                        hat |= 2;
+                    }
+                    if y > 0 {
+                        hat |= 4;
+                    }
+                    if x < 0 {
+                        hat |= 8;
+                    }
+                    hat
+                })
+                .collect(),
+        }
+    }
+}
+
+/// 设备使用的映射：找到 GameControllerDB 映射时按映射解析，否则使用内核的标准手柄布局
+#[derive(Debug, Clone)]
+struct DeviceMapping {
+    layout: RawLayout,
+    mapping: Option<ControllerMapping>,
+    /// 设备具备的扩展按键（Guide、背键等）
+    extended: Vec<Buttons>,
+    /// 没有对应逻辑按键的原始按键，以 Buttons::Other 上报
+    others: Vec<KeyCode>,
+}
+
+impl DeviceMapping {
+    fn new(device: &Device, mapping: Option<ControllerMapping>) -> Self {
+        let layout = RawLayout::from_device(device);
+        let (extended, others) = match &mapping {
+            Some(mapping) => {
+                let bound: HashSet<usize> = mapping
+                    .bindings
+                    .iter()
+                    .filter_map(|binding| match binding.source {
+                        RawSource::Button(index) => Some(index),
+                        _ => None,
+                    })
+                    .collect();
+                let extended = mapping
+                    .bindings
+                    .iter()
+                    .filter_map(|binding| match &binding.target {
+                        MappingTarget::Button(btn) if !is_standard_button(btn) => Some(btn.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                let others = layout
+                    .buttons
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| !bound.contains(index))
+                    .map(|(_, key)| *key)
+                    .collect();
+                (extended, others)
+            }
+            None => {
+                let extended = EXTENDED_BUTTONS_MAP
+                    .iter()
+                    .filter(|(_, key)| layout.buttons.contains(key))
+                    .map(|(btn, _)| btn.clone())
+                    .collect();
+                let others = layout
+                    .buttons
+                    .iter()
+                    .filter(|key| {
+                        !BUTTONS_MAP
+                            .iter()
+                            .chain(EXTENDED_BUTTONS_MAP.iter())
+                            .any(|(_, known)| known == *key)
+                    })
+                    .copied()
+                    .collect();
+                (extended, others)
+            }
+        };
+        DeviceMapping {
+            layout,
+            mapping,
+            extended,
+            others,
+        }
+    }
+
+    fn state(
+        &self,
+        abs: &HashMap<AbsoluteAxisCode, AbsAxis>,
+        keys: &AttributeSet<KeyCode>,
+    ) -> EvdevState {
+        let mut state = match &self.mapping {
+            Some(mapping) => {
+                let mapped = mapping.apply(&self.layout.read(abs, keys));
+                let (lx, ly, rx, ry) = mapped.thumbs;
+                EvdevState {
+                    raw_thumbs: [
+                        (Axes::LeftThumbX, lx),
+                        (Axes::LeftThumbY, ly),
+                        (Axes::RightThumbX, rx),
+                        (Axes::RightThumbY, ry),
+                    ]
+                    .map(|(axis, v)| {
+                        self.native_thumb(abs, &axis)
+                            .unwrap_or((v as i32, NativeRange::I16))
+                    }),
+                    thumbs: mapped.thumbs,
+                    triggers: mapped.triggers,
+                    pressed: mapped.pressed,
+                }
+            }
+            None => EvdevState::from_raw(abs, keys),
+        };
+        state.pressed.extend(
+            self.others
+                .iter()
+                .filter(|key| keys.contains(**key))
+                .map(|key| Buttons::Other(key.0)),
+        );
+        state
+    }
+
+    /// 整轴映射到整轴的摇杆沿用源轴的原生值与 absinfo，反向留到归一化时处理；
+    /// 半轴、按键或十字键组成的摇杆没有单一的原生值，返回 None
+    fn native_thumb(
+        &self,
+        abs: &HashMap<AbsoluteAxisCode, AbsAxis>,
+        axis: &Axes,
+    ) -> Option<(i32, NativeRange)> {
+        let mapping = self.mapping.as_ref()?;
+        let mut bindings = mapping.bindings.iter().filter(
+            |binding| matches!(&binding.target, MappingTarget::Axis(target, _) if target == axis),
+        );
+        let binding = bindings.next()?;
+        if bindings.next().is_some() {
+            return None;
+        }
+        match (&binding.target, binding.source) {
+            (
+                MappingTarget::Axis(_, AxisRange::Full),
+                RawSource::Axis {
+                    index,
+                    range: AxisRange::Full,
+                    invert,
+                },
+            ) => {
+                let info = abs.get(self.layout.axes.get(index)?).copied()?;
+                // 映射按 SDL 约定 Y 轴向下为正，与 XInput 相反
+                let flip = matches!(axis, Axes::LeftThumbY | Axes::RightThumbY);
+                Some((info.value, info.range(invert != flip)))
+            }
+            _ => None,
+        }
+    }
+
+    /// 除标准按键外设备具备的所有按键
+    fn extra_buttons(&self) -> Vec<Buttons> {
+        self.extended
+            .iter()
+            .cloned()
+            .chain(self.others.iter().map(|key| Buttons::Other(key.0)))
+            .collect()
+    }
+
+    /// 设备实际具备的按键（不含扳机）；get_controller 总会补齐标准按键
+    fn buttons(&self) -> Vec<Buttons> {
+        let has_hat = self
+            .layout
+            .hats
+            .iter()
+            .any(|(x, _)| *x == AbsoluteAxisCode::ABS_HAT0X);
+        BUTTONS_MAP
+            .iter()
+            .filter(|(btn, key)| match &self.mapping {
+                Some(mapping) => mapping.bindings.iter().any(
+                    |binding| matches!(&binding.target, MappingTarget::Button(target) if target == btn),
+                ),
+                None => self.layout.buttons.contains(key) || (has_hat && is_dpad(btn)),
+            })
+            .map(|(btn, _)| btn.clone())
+            .chain(self.extra_buttons())
+            .collect()
+    }
+
+    /// 摇杆轴对应的原生轴
+    fn stick_axis(&self, axis: &Axes) -> Option<AbsoluteAxisCode> {
+        match &self.mapping {
+            Some(mapping) => mapping.bindings.iter().find_map(|binding| {
+                match (&binding.target, binding.source) {
+                    (MappingTarget::Axis(target, _), RawSource::Axis { index, .. })
+                        if target == axis =>
+                    {
+                        self.layout.axes.get(index).copied()
+                    }
+                    _ => None,
+                }
+            }),
+            None => NATIVE_AXES
+                .iter()
+                .find(|(native, _)| native == axis)
+                .map(|(_, code)| *code)
+                .filter(|code| self.layout.axes.contains(code)),
+        }
+    }
+
+    /// 扳机类型与模拟扳机的原生轴；映射到按键或十字键的扳机只有开关量
+    fn trigger(&self, trigger: &Buttons) -> (TriggerType, Option<AbsoluteAxisCode>) {
+        let Some(mapping) = &self.mapping else {
+            let native = NATIVE_TRIGGERS
+                .iter()
+                .find(|(native, _)| native == trigger)
+                .map(|(_, code)| *code)
+                .filter(|code| self.layout.axes.contains(code));
+            return match native {
+                Some(code) => (TriggerType::Analog, Some(code)),
+                None => (TriggerType::None, None),
+            };
+        };
+        let source = mapping.bindings.iter().find_map(|binding| {
+            matches!(&binding.target, MappingTarget::Button(target) if target == trigger)
+                .then_some(binding.source)
+        });
+        match source {
+            Some(RawSource::Axis { index, .. }) => {
+                (TriggerType::Analog, self.layout.axes.get(index).copied())
+            }
+            Some(_) => (TriggerType::Digital, None),
+            None => (TriggerType::None, None),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct OpenedDevice {
+    device: Device,
+    mapping: DeviceMapping,
+    applied: AppliedMapping,
+    /// 关联的 /sys/class/power_supply 条目，有线手柄通常没有
+    power_supply: Option<PathBuf>,
+    /// 电池属性变化缓慢，缓存读取结果
+    power: Option<(Instant, PowerInfo)>,
+    /// 已上传的 FF_RUMBLE 效果，之后只更新参数
+    rumble: Option<FFEffect>,
+    /// /sys/class/leds 下属于该手柄的指示灯
+    leds: LedSet,
+}
+
+impl OpenedDevice {
+    fn power(&mut self) -> PowerInfo {
+        match self.power {
+            Some((read_at, power)) if read_at.elapsed() < POWER_REFRESH_INTERVAL => power,
+            _ => {
+                let power = read_power_supply(
+                    self.power_supply.as_deref(),
+                    self.device.input_id().bus_type().0,
+                );
+                self.power = Some((Instant::now(), power));
+                power
+            }
+        }
+    }
+}
+
+/// 一次完整上报（以 SYN_REPORT 结束），时间戳由内核填写
+#[derive(Debug, Clone)]
+pub struct EvdevReport {
+    pub timestamp: SystemTime,
+    pub state: EvdevState,
+}
+
+/// 对单个设备的阻塞事件读取
+#[derive(Debug)]
+pub struct EvdevEventStream {
+    id: u32,
+    device: Device,
+    mapping: DeviceMapping,
+    abs: HashMap<AbsoluteAxisCode, AbsAxis>,
+    keys: AttributeSet<KeyCode>,
+}
+
+impl EvdevEventStream {
+    /// 等待最多 timeout，返回期间收到的所有完整上报；设备断开时返回 Err
+    pub fn next_reports(&mut self, timeout: Duration) -> Result<Vec<EvdevReport>, GamepadError> {
+        if !wait_readable(&self.device, self.id, timeout)? {
+            return Ok(Vec::new());
+        }
+
+        let id = self.id;
+        let events: Vec<_> = self
+            .device
+            .fetch_events()
+            .map_err(|e| GamepadError::from_device_io(id, e))?
+            .collect();
+
+        let mut reports = Vec::new();
+        for event in events {
+            let timestamp = event.timestamp();
+            match event.destructure() {
+                EventSummary::AbsoluteAxis(_, code, value) => {
+                    self.abs.entry(code).or_default().value = value;
+                }
+                EventSummary::Key(_, code, value) => {
+                    if value == 0 {
+                        self.keys.remove(code);
+                    } else {
+                        self.keys.insert(code);
+                    }
+                }
+                EventSummary::Synchronization(_, SynchronizationCode::SYN_REPORT, _) => {
+                    reports.push(EvdevReport {
+                        timestamp,
+                        state: self.mapping.state(&self.abs, &self.keys),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(reports)
+    }
+}
+
+/// 一次 IMU 上报，时间戳优先按设备的硬件时间戳推算
+#[derive(Debug, Clone, Copy)]
+pub struct MotionReport {
+    pub timestamp: SystemTime,
+    pub accel: (f64, f64, f64),
+    pub gyro: (f64, f64, f64),
+}
+
+/// 对 IMU 子设备（如 "DualSense Wireless Controller Motion Sensors"）的阻塞事件读取
+#[derive(Debug)]
+pub struct MotionEventStream {
+    id: u32,
+    device: Device,
+    /// 各轴每个单位对应的 g 或 deg/s，由 absinfo 的 resolution 换算
+    scale: [f64; 6],
+    values: [i32; 6],
+    last_hw_timestamp: Option<u32>,
+    /// 首个硬件时间戳对应的内核时间，以及此后累计的硬件时间 (us)
+    hw_clock: Option<(SystemTime, u64)>,
+}
+
+impl MotionEventStream {
+    fn new(id: u32, device: Device) -> Result<Self, GamepadError> {
+        let mut scale = [1.0; 6];
+        let mut values = [0; 6];
+        for (code, info) in device
+            .get_absinfo()
+            .map_err(|e| GamepadError::from_device_io(id, e))?
+        {
+            if let Some(i) = MOTION_AXES.iter().position(|axis| *axis == code) {
+                values[i] = info.value();
+                if info.resolution() > 0 {
+                    scale[i] = 1.0 / info.resolution() as f64;
+                }
+            }
+        }
+        Ok(MotionEventStream {
+            id,
+            device,
+            scale,
+            values,
+            last_hw_timestamp: None,
+            hw_clock: None,
+        })
+    }
+
+    /// 等待最多 timeout，返回期间收到的所有 IMU 上报；设备断开时返回 Err
+    pub fn next_reports(&mut self, timeout: Duration) -> Result<Vec<MotionReport>, GamepadError> {
+        if !wait_readable(&self.device, self.id, timeout)? {
+            return Ok(Vec::new());
+        }
+
+        let id = self.id;
+        let events: Vec<_> = self
+            .device
+            .fetch_events()
+            .map_err(|e| GamepadError::from_device_io(id, e))?
+            .collect();
+
+        let mut reports = Vec::new();
+        for event in events {
+            let timestamp = event.timestamp();
+            match event.destructure() {
+                EventSummary::AbsoluteAxis(_, code, value) => {
+                    if let Some(i) = MOTION_AXES.iter().position(|axis| *axis == code) {
+                        self.values[i] = value;
+                    }
+                }
+                // 硬件时间戳 (us) 为 32 位，会回绕
+                EventSummary::Misc(_, MiscCode::MSC_TIMESTAMP, value) => {
+                    let hw_timestamp = value as u32;
+                    let elapsed = self
+                        .last_hw_timestamp
+                        .map_or(0, |last| hw_timestamp.wrapping_sub(last) as u64);
+                    self.last_hw_timestamp = Some(hw_timestamp);
+                    self.hw_clock = Some(match self.hw_clock {
+                        Some((base, total)) => (base, total + elapsed),
+                        None => (timestamp, 0),
+                    });
+                }
+                EventSummary::Synchronization(_, SynchronizationCode::SYN_REPORT, _) => {
+                    let v = |i: usize| self.values[i] as f64 * self.scale[i];
+                    reports.push(MotionReport {
+                        timestamp: match self.hw_clock {
+                            Some((base, total)) => base + Duration::from_micros(total),
+                            None => timestamp,
+                        },
+                        accel: (v(0), v(1), v(2)),
+                        gyro: (v(3), v(4), v(5)),
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(reports)
+    }
+}
+
+impl MotionReport {
+    pub fn to_sample(self, epoch: SystemTime) -> MotionSample {
+        MotionSample {
+            timestamp: self
+                .timestamp
+                .duration_since(epoch)
+                .unwrap_or_default()
+                .as_micros() as u64,
+            accel: self.accel,
+            gyro: self.gyro,
+        }
+    }
+}
+
+/// 一次触摸板上报（以 SYN_REPORT 结束）
+#[derive(Debug, Clone)]
+pub struct TouchpadFrame {
+    pub timestamp: SystemTime,
+    pub contacts: Vec<TouchContact>,
+    pub clicked: bool,
+}
+
+impl TouchpadFrame {
+    pub fn to_report(&self, epoch: SystemTime) -> TouchpadReport {
+        TouchpadReport {
+            timestamp: self
+                .timestamp
+                .duration_since(epoch)
+                .unwrap_or_default()
+                .as_micros() as u64,
+            contacts: self.contacts.clone(),
+            clicked: self.clicked,
+        }
+    }
+}
+
+/// 对触摸板子设备（如 "Wireless Controller Touchpad"）的阻塞事件读取，按多点触控 B 协议解析
+#[derive(Debug)]
+pub struct TouchpadEventStream {
+    id: u32,
+    device: Device,
+    x_range: (i32, i32),
+    y_range: (i32, i32),
+    slot: usize,
+    /// 各槽位的 (是否按下, x, y) 原始值
+    slots: Vec<(bool, i32, i32)>,
+    clicked: bool,
+}
+
+impl TouchpadEventStream {
+    fn new(id: u32, device: Device) -> Result<Self, GamepadError> {
+        let abs = read_abs(&device, id)?;
+        let range = |code: AbsoluteAxisCode| abs.get(&code).map_or((0, 0), |a| (a.min, a.max));
+        let slot_count = abs
+            .get(&AbsoluteAxisCode::ABS_MT_SLOT)
+            .map_or(1, |slot| slot.max.max(0) as usize + 1);
+        let clicked = read_keys(&device, id)?.contains(KeyCode::BTN_LEFT);
+        Ok(TouchpadEventStream {
+            id,
+            device,
+            x_range: range(AbsoluteAxisCode::ABS_MT_POSITION_X),
+            y_range: range(AbsoluteAxisCode::ABS_MT_POSITION_Y),
+            slot: 0,
+            slots: vec![(false, 0, 0); slot_count],
+            clicked,
+        })
+    }
+
+    /// 等待最多 timeout，返回期间收到的所有触摸板上报；设备断开时返回 Err
+    pub fn next_frames(&mut self, timeout: Duration) -> Result<Vec<TouchpadFrame>, GamepadError> {
+        if !wait_readable(&self.device, self.id, timeout)? {
+            return Ok(Vec::new());
+        }
+
+        let id = self.id;
+        let events: Vec<_> = self
+            .device
+            .fetch_events()
+            .map_err(|e| GamepadError::from_device_io(id, e))?
+            .collect();
+
+        let mut frames = Vec::new();
+        for event in events {
+            let timestamp = event.timestamp();
+            match event.destructure() {
+                EventSummary::AbsoluteAxis(_, AbsoluteAxisCode::ABS_MT_SLOT, value) => {
+                    self.slot = value.max(0) as usize;
+                }
+                EventSummary::AbsoluteAxis(_, code, value) => {
+                    let Some(slot) = self.slots.get_mut(self.slot) else {
+                        continue;
+                    };
+                    match code {
+                        // 跟踪 ID 为 -1 表示手指抬起
+                        AbsoluteAxisCode::ABS_MT_TRACKING_ID => slot.0 = value >= 0,
+                        AbsoluteAxisCode::ABS_MT_POSITION_X => slot.1 = value,
+                        AbsoluteAxisCode::ABS_MT_POSITION_Y => slot.2 = value,
+                        _ => {}
+                    }
+                }
+                // 触摸板按下以 BTN_LEFT 上报
+                EventSummary::Key(_, KeyCode::BTN_LEFT, value) => {
+                    self.clicked = value != 0;
+                }
+                EventSummary::Synchronization(_, SynchronizationCode::SYN_REPORT, _) => {
+                    frames.push(TouchpadFrame {
+                        timestamp,
+                        contacts: self.contacts(),
+                        clicked: self.clicked,
+                    });
+                }
+                _ => {}
+            }
+        }
+        Ok(frames)
+    }
+
+    fn contacts(&self) -> Vec<TouchContact> {
+        let normalize = |v: i32, (min, max): (i32, i32)| {
+            if max <= min {
+                0.0
+            } else {
+                ((v - min) as f64 / (max - min) as f64).clamp(0.0, 1.0)
+            }
+        };
+        self.slots
+            .iter()
+            .enumerate()
+            .map(|(id, &(pressed, x, y))| TouchContact {
+                id: id as u32,
+                x: normalize(x, self.x_range),
+                y: normalize(y, self.y_range),
+                pressed,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct Evdev {
+    state: Mutex<EvdevState>,
+    devices: Mutex<HashMap<u32, OpenedDevice>>,
+    mapping_store: RwLock<Arc<MappingStore>>,
+}
+
+impl Evdev {
+    /// 为事件驱动采样单独打开设备，避免与状态轮询共享读取缓冲
+    pub fn open_event_stream(&self, id: u32) -> Result<EvdevEventStream, GamepadError> {
+        let OpenedDevice {
+            device, mapping, ..
+        } = self.open(id)?;
+        let abs = read_abs(&device, id)?;
+        let keys = read_keys(&device, id)?;
+        Ok(EvdevEventStream {
+            id,
+            device,
+            mapping,
+            abs,
+            keys,
+        })
+    }
+
+    /// 打开同一手柄的 IMU 子设备
+    pub fn open_motion_stream(&self, id: u32) -> Result<MotionEventStream, GamepadError> {
+        let motion_id = find_sibling(id, is_motion_sensor).ok_or_else(|| {
+            GamepadError::BackendUnavailable(format!("Device ID {} has no motion sensors", id))
+        })?;
+        MotionEventStream::new(motion_id, open_device(motion_id)?)
+    }
+
+    /// 打开同一手柄的触摸板子设备
+    pub fn open_touchpad_stream(&self, id: u32) -> Result<TouchpadEventStream, GamepadError> {
+        let touchpad_id = find_sibling(id, is_touchpad).ok_or_else(|| {
+            GamepadError::BackendUnavailable(format!("Device ID {} has no touchpad", id))
+        })?;
+        TouchpadEventStream::new(touchpad_id, open_device(touchpad_id)?)
+    }
+
+    /// 替换映射来源，已打开的设备会在下次读取时按新映射重新打开
+    pub fn set_mapping_store(&self, mapping_store: Arc<MappingStore>) {
+        if let Ok(mut current) = self.mapping_store.write() {
+            *current = mapping_store;
+        }
+        self.reload_mappings();
+    }
+
+    pub fn reload_mappings(&self) {
+        if let Ok(mut devices) = self.devices.lock() {
+            devices.clear();
+        }
+    }
+
+    pub fn applied_mapping(&self, id: u32) -> Result<AppliedMapping, GamepadError> {
+        let opened = self
+            .devices
+            .lock()
+            .ok()
+            .and_then(|devices| devices.get(&id).map(|d| d.applied.clone()));
+        match opened {
+            Some(applied) => Ok(applied),
+            None => self.open(id).map(|opened| opened.applied),
+        }
+    }
+
+    /// 读取未经映射的原始输入，供映射向导使用
+    pub fn read_raw(&self, id: u32) -> Result<RawJoystick, GamepadError> {
+        let mut devices = self
+            .devices
+            .lock()
+            .map_err(|_| GamepadError::lock("devices"))?;
+        if let Entry::Vacant(entry) = devices.entry(id) {
+            entry.insert(self.open(id)?);
+        }
+        let opened = devices
+            .get(&id)
+            .ok_or_else(|| GamepadError::not_connected(id))?;
+        let abs = read_abs(&opened.device, id)?;
+        let keys = read_keys(&opened.device, id)?;
+        Ok(opened.mapping.layout.read(&abs, &keys))
+    }
+
+    fn with_leds<T>(&self, id: u32, f: impl FnOnce(&LedSet) -> T) -> Result<T, GamepadError> {
+        let mut devices = self
+            .devices
+            .lock()
+            .map_err(|_| GamepadError::lock("devices"))?;
+        if let Entry::Vacant(entry) = devices.entry(id) {
+            entry.insert(self.open(id)?);
+        }
+        let opened = devices
+            .get(&id)
+            .ok_or_else(|| GamepadError::not_connected(id))?;
+        Ok(f(&opened.leds))
+    }
+
+    fn open(&self, id: u32) -> Result<OpenedDevice, GamepadError> {
+        let device = open_device(id)?;
+        let device_dir = sysfs_device_dir(id);
+        let name = device_name(&device, id);
+        let guid = device_guid(&device, &name);
+        let found = self
+            .mapping_store
+            .read()
+            .ok()
+            .and_then(|store| store.lookup(&guid));
+        let applied = AppliedMapping {
+            guid,
+            name,
+            source: found
+                .as_ref()
+                .map_or(MappingSource::Native, |(_, source)| *source),
+            mapping: found.as_ref().map(|(mapping, _)| mapping.to_sdl_string()),
+        };
+        Ok(OpenedDevice {
+            mapping: DeviceMapping::new(&device, found.map(|(mapping, _)| mapping)),
+            device,
+            applied,
+            power_supply: device_dir.as_deref().and_then(find_power_supply),
+            power: None,
+            rumble: None,
+            leds: device_dir.as_deref().map(LedSet::find).unwrap_or_default(),
+        })
+    }
+}
+
+impl RawInput<EvdevState, GamepadError> for Evdev {
+    fn new() -> Self {
+        Evdev {
+            state: Mutex::new(EvdevState::default()),
+            devices: Mutex::new(HashMap::new()),
+            mapping_store: RwLock::new(Arc::new(MappingStore::load(None))),
+        }
+    }
+
+    fn set_state(&self, state: EvdevState) {
+        if let Ok(mut current_state) = self.state.lock() {
+            *current_state = state;
+        }
+    }
+
+    fn get_state(&self) -> Result<EvdevState, GamepadError> {
+        if let Ok(current_state) = self.state.lock() {
+            return Ok(current_state.clone());
+        }
+        Err(GamepadError::lock("state"))
+    }
+
+    fn update(&self, id: u32) -> Result<EvdevState, GamepadError> {
+        let mut devices = self
+            .devices
+            .lock()
+            .map_err(|_| GamepadError::lock("devices"))?;
+        if let Entry::Vacant(entry) = devices.entry(id) {
+            entry.insert(self.open(id)?);
+        }
+
+        let read = devices
+            .get(&id)
+            .ok_or_else(|| GamepadError::not_connected(id))
+            .and_then(|opened| {
+                let abs = read_abs(&opened.device, id)?;
+                let keys = read_keys(&opened.device, id)?;
+                Ok(opened.mapping.state(&abs, &keys))
+            });
+
+        let state = match read {
+            Ok(state) => state,
+            Err(e) => {
+                // 读取失败通常意味着设备已拔出，下次重新打开
+                devices.remove(&id);
+                self.set_state(EvdevState::default());
+                return Err(e);
+            }
+        };
+        self.set_state(state.clone());
+        Ok(state)
+    }
+
+    fn all_device_id(&self) -> Vec<u32> {
+        let mut device_ids: Vec<u32> = evdev::enumerate()
+            .filter(|(_, device)| is_gamepad(device))
+            .filter_map(|(path, _)| event_id(&path))
+            .collect();
+        device_ids.sort();
+        device_ids
+    }
+
+    fn get_controller(&self, id: u32) -> Result<Gamepad, GamepadError> {
+        let state = self.get_state()?;
+        let (name, guid, input_id, serial, power, extra_buttons) = {
+            let mut devices = self
+                .devices
+                .lock()
+                .map_err(|_| GamepadError::lock("devices"))?;
+            let opened = devices
+                .get_mut(&id)
+                .ok_or_else(|| GamepadError::not_connected(id))?;
+            (
+                opened.applied.name.clone(),
+                opened.applied.guid.clone(),
+                opened.device.input_id(),
+                opened.device.unique_name().map(str::to_string),
+                opened.power(),
+                opened.mapping.extra_buttons(),
+            )
+        };
+
+        let mut gamepad = Gamepad {
+            id,
+            name,
+            vendor_id: Some(input_id.vendor()),
+            product_id: Some(input_id.product()),
+            version: Some(input_id.version()),
+            bus_type: Some(input_id.bus_type().0),
+            serial,
+            guid,
+            power,
+            axes: HashMap::new(),
+            buttons: HashMap::new(),
+        };
+        let (lx, ly, rx, ry) = state.thumbs;
+        for ((axis, value), (raw, range)) in [
+            (Axes::LeftThumbX, lx),
+            (Axes::LeftThumbY, ly),
+            (Axes::RightThumbX, rx),
+            (Axes::RightThumbY, ry),
+        ]
+        .into_iter()
+        .zip(state.raw_thumbs)
+        {
+            gamepad.axes.insert(
+                axis.clone(),
+                Axis {
+                    axis,
+                    value,
+                    raw,
+                    range,
+                },
+            );
+        }
+        BUTTONS_MAP
+            .iter()
+            .map(|(btn, _)| btn)
+            .chain(extra_buttons.iter())
+            .for_each(|btn| {
+                let pressed = state.pressed.contains(btn);
+                let val = if pressed { 255 } else { 0 };
+                gamepad.buttons.insert(
+                    btn.clone(),
+                    Button {
+                        button: btn.clone(),
+                        is_pressed: pressed,
+                        value: val,
+                    },
+                );
+            });
+        for (btn, value) in [
+            (Buttons::LeftTrigger, state.triggers.0),
+            (Buttons::RightTrigger, state.triggers.1),
+        ] {
+            gamepad.buttons.insert(
+                btn.clone(),
+                Button {
+                    button: btn,
+                    is_pressed: value > 0,
+                    value,
+                },
+            );
+        }
+        Ok(gamepad)
+    }
+
+    fn get_axis_val(&self) -> Option<(i16, i16, i16, i16)> {
+        self.state.lock().ok().map(|state| state.thumbs)
+    }
+}
+
+impl Capabilities<GamepadError> for Evdev {
+    fn get_capabilities(&self, id: u32) -> Result<DeviceCapabilities, GamepadError> {
+        let mut devices = self
+            .devices
+            .lock()
+            .map_err(|_| GamepadError::lock("devices"))?;
+        if let Entry::Vacant(entry) = devices.entry(id) {
+            entry.insert(self.open(id)?);
+        }
+        let opened = devices
+            .get(&id)
+            .ok_or_else(|| GamepadError::not_connected(id))?;
+        let abs = read_abs(&opened.device, id)?;
+        let capability = |name: String, code: AbsoluteAxisCode| {
+            abs.get(&code).map(|info| AxisCapability {
+                name,
+                source: Some(format!("{:?}", code)),
+                min: info.min,
+                max: info.max,
+                flat: info.flat,
+                fuzz: info.fuzz,
+                resolution: info.resolution,
+            })
+        };
+
+        let mut axes: Vec<AxisCapability> = NATIVE_AXES
+            .iter()
+            .filter_map(|(axis, _)| capability(axis.to_string(), opened.mapping.stick_axis(axis)?))
+            .collect();
+        let (left_trigger, left_axis) = opened.mapping.trigger(&Buttons::LeftTrigger);
+        let (right_trigger, right_axis) = opened.mapping.trigger(&Buttons::RightTrigger);
+        for (btn, code) in [
+            (Buttons::LeftTrigger, left_axis),
+            (Buttons::RightTrigger, right_axis),
+        ] {
+            axes.extend(code.and_then(|code| capability(btn.to_string(), code)));
+        }
+
+        Ok(DeviceCapabilities {
+            axes,
+            buttons: opened
+                .mapping
+                .buttons()
+                .iter()
+                .map(|btn| btn.to_string())
+                .collect(),
+            left_trigger,
+            right_trigger,
+            rumble: opened
+                .device
+                .supported_ff()
+                .is_some_and(|ff| ff.contains(FFEffectCode::FF_RUMBLE)),
+            motion: find_sibling(id, is_motion_sensor).is_some(),
+            touchpad: find_sibling(id, is_touchpad).is_some(),
+            battery: opened.power_supply.is_some(),
+            leds: opened.leds.info(),
+        })
+    }
+}
+
+impl Rumble<GamepadError> for Evdev {
+    fn set_rumble(
+        &self,
+        id: u32,
+        low: u16,
+        high: u16,
+        duration: Duration,
+    ) -> Result<(), GamepadError> {
+        let mut devices = self
+            .devices
+            .lock()
+            .map_err(|_| GamepadError::lock("devices"))?;
+        if let Entry::Vacant(entry) = devices.entry(id) {
+            entry.insert(self.open(id)?);
+        }
+        let opened = devices
+            .get_mut(&id)
+            .ok_or_else(|| GamepadError::not_connected(id))?;
+
+        if low == 0 && high == 0 {
+            if let Some(effect) = opened.rumble.as_mut() {
+                effect
+                    .stop()
+                    .map_err(|e| GamepadError::from_device_io(id, e))?;
+            }
+            return Ok(());
+        }
+        if !opened
+            .device
+            .supported_ff()
+            .is_some_and(|ff| ff.contains(FFEffectCode::FF_RUMBLE))
+        {
+            return Err(GamepadError::BackendUnavailable(format!(
+                "Device ID {} does not support rumble",
+                id
+            )));
+        }
+
+        let data = FFEffectData {
+            direction: 0,
+            trigger: FFTrigger {
+                button: 0,
+                interval: 0,
+            },
+            replay: FFReplay {
+                length: duration.as_millis().min(u16::MAX as u128) as u16,
+                delay: 0,
+            },
+            kind: FFEffectKind::Rumble {
+                strong_magnitude: low,
+                weak_magnitude: high,
+            },
+        };
+        let effect = match opened.rumble.as_mut() {
+            Some(effect) => {
+                effect
+                    .update(data)
+                    .map_err(|e| GamepadError::from_device_io(id, e))?;
+                effect
+            }
+            None => {
+                let effect = opened
+                    .device
+                    .upload_ff_effect(data)
+                    .map_err(|e| GamepadError::from_device_io(id, e))?;
+                opened.rumble.insert(effect)
+            }
+        };
+        effect
+            .play(1)
+            .map_err(|e| GamepadError::from_device_io(id, e))
+    }
+}
+
+// 写 /sys/class/leds 通常需要 root 或 udev 规则，失败时返回 PermissionDenied
+impl Leds<GamepadError> for Evdev {
+    fn get_leds(&self, id: u32) -> Result<LedInfo, GamepadError> {
+        self.with_leds(id, LedSet::info)
+    }
+
+    fn set_player_led(&self, id: u32, player: u8) -> Result<(), GamepadError> {
+        let info = self.get_leds(id)?;
+        if player > info.player_count {
+            return Err(GamepadError::invalid(format!(
+                "Player must be within 0..={}, got {}",
+                info.player_count, player
+            )));
+        }
+        match self.with_leds(id, |leds| leds.set_player(player))? {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(GamepadError::BackendUnavailable(format!(
+                "Device ID {} has no player LEDs",
+                id
+            ))),
+            Err(e) => Err(GamepadError::from_device_io(id, e)),
+        }
+    }
+
+    fn set_lightbar(&self, id: u32, red: u8, green: u8, blue: u8) -> Result<(), GamepadError> {
+        match self.with_leds(id, |leds| leds.set_lightbar(red, green, blue))? {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(GamepadError::BackendUnavailable(format!(
+                "Device ID {} has no lightbar",
+                id
+            ))),
+            Err(e) => Err(GamepadError::from_device_io(id, e)),
+        }
+    }
+}
+
+fn open_device(id: u32) -> Result<Device, GamepadError> {
+    Device::open(format!("{}/event{}", INPUT_DIR, id))
+        .map_err(|e| GamepadError::from_device_io(id, e))
+}
+
+fn event_id(path: &Path) -> Option<u32> {
+    path.file_name()?
+        .to_str()?
+        .strip_prefix("event")?
+        .parse::<u32>()
+        .ok()
+}
+
+/// eventN 所属的物理设备目录（如 HID 设备），同一手柄的各个子设备共享该目录
+fn sysfs_device_dir(id: u32) -> Option<PathBuf> {
+    fs::canonicalize(Path::new(SYS_INPUT_DIR).join(format!("event{}/device/device", id))).ok()
+}
+
+/// 查找同一手柄的其他 event 节点（IMU、触摸板等）
+fn find_sibling(id: u32, filter: fn(&Device) -> bool) -> Option<u32> {
+    let device_dir = sysfs_device_dir(id)?;
+    evdev::enumerate()
+        .filter(|(_, device)| filter(device))
+        .filter_map(|(path, _)| event_id(&path))
+        .find(|other| *other != id && sysfs_device_dir(*other).as_ref() == Some(&device_dir))
+}
+
+/// 等待设备可读；超时返回 false，设备断开时返回 Err
+fn wait_readable(device: &Device, id: u32, timeout: Duration) -> Result<bool, GamepadError> {
+    let mut poll_fd = libc::pollfd {
+        fd: device.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    let ready = unsafe { libc::poll(&mut poll_fd, 1, timeout.as_millis() as libc::c_int) };
+    if ready < 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    if ready == 0 {
+        return Ok(false);
+    }
+    if poll_fd.revents & (libc::POLLERR | libc::POLLHUP | libc::POLLNVAL) != 0 {
+        return Err(GamepadError::not_connected(id));
+    }
+    Ok(true)
+}
+
+fn read_keys(device: &Device, id: u32) -> Result<AttributeSet<KeyCode>, GamepadError> {
+    device
+        .get_key_state()
+        .map_err(|e| GamepadError::from_device_io(id, e))
+}
+
+fn device_name(device: &Device, id: u32) -> String {
+    device
+        .name()
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("evdev Controller {}", id))
+}
+
+fn device_guid(device: &Device, name: &str) -> String {
+    let input_id = device.input_id();
+    create_guid(
+        input_id.bus_type().0,
+        input_id.vendor(),
+        input_id.product(),
+        input_id.version(),
+        name,
+        0,
+    )
+}
+
+fn read_abs(device: &Device, id: u32) -> Result<HashMap<AbsoluteAxisCode, AbsAxis>, GamepadError> {
+    Ok(device
+        .get_absinfo()
+        .map_err(|e| GamepadError::from_device_io(id, e))?
+        .map(|(code, info)| {
+            (
+                code,
+                AbsAxis {
+                    value: info.value(),
+                    min: info.minimum(),
+                    max: info.maximum(),
+                    flat: info.flat(),
+                    fuzz: info.fuzz(),
+                    resolution: info.resolution(),
+                },
+            )
+        })
+        .collect())
+}
+
+fn is_gamepad(device: &Device) -> bool {
+    let has_axes = device
+        .supported_absolute_axes()
+        .is_some_and(|axes| axes.iter().next().is_some());
+    device
+        .supported_keys()
+        .is_some_and(|keys| has_gamepad_caps(keys.iter().map(|key| key.code()), has_axes))
+}
+
+// 与 SDL 一致：至少一个 BTN_JOYSTICK/BTN_GAMEPAD 区段的按键，并且有绝对轴
+fn has_gamepad_caps(mut keys: impl Iterator<Item = u16>, has_axes: bool) -> bool {
+    has_axes && keys.any(|code| (BTN_JOYSTICK..BTN_GAMEPAD_END).contains(&code))
+}
+
+fn is_motion_sensor(device: &Device) -> bool {
+    device.properties().contains(PropType::ACCELEROMETER)
+}
+
+fn is_touchpad(device: &Device) -> bool {
+    !is_motion_sensor(device)
+        && device
+            .supported_absolute_axes()
+            .is_some_and(|axes| axes.contains(AbsoluteAxisCode::ABS_MT_POSITION_X))
+}
+
+fn is_dpad(btn: &Buttons) -> bool {
+    matches!(
+        btn,
+        Buttons::DPadUp | Buttons::DPadDown | Buttons::DPadLeft | Buttons::DPadRight
+    )
+}
+
+fn is_standard_button(btn: &Buttons) -> bool {
+    matches!(btn, Buttons::LeftTrigger | Buttons::RightTrigger)
+        || BUTTONS_MAP.iter().any(|(standard, _)| standard == btn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gamepad_buttons_with_axes() {
+        let south = KeyCode::BTN_SOUTH.code();
+        assert!(has_gamepad_caps([south].into_iter(), true));
+        assert!(!has_gamepad_caps([south].into_iter(), false));
+    }
+
+    #[test]
+    fn joystick_buttons_with_axes() {
+        // 飞行摇杆只有 BTN_TRIGGER/BTN_THUMB 等，不带 BTN_SOUTH
+        let keys = [KeyCode::BTN_TRIGGER.code(), KeyCode::BTN_THUMB.code()];
+        assert!(has_gamepad_caps(keys.into_iter(), true));
+    }
+
+    #[test]
+    fn non_gamepad_keys_rejected() {
+        // 触摸板与键盘的按键都不在手柄区段内
+        let keys = [
+            KeyCode::BTN_LEFT.code(),
+            KeyCode::BTN_TOUCH.code(),
+            KeyCode::KEY_A.code(),
+        ];
+        assert!(!has_gamepad_caps(keys.into_iter(), true));
+        assert!(!has_gamepad_caps(std::iter::empty(), true));
+    }
+}
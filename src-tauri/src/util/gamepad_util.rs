@@ -1,79 +1,145 @@
-use crate::util::input_wrapper::{RawInput, XInput};
+use crate::util::capabilities::DeviceCapabilities;
+use crate::util::chatter::ButtonLog;
+use crate::util::error::GamepadError;
+#[cfg(target_os = "linux")]
+use crate::util::evdev_input::{
+    Evdev as Backend, EvdevEventStream, MotionEventStream, MotionReport, TouchpadEventStream,
+    TouchpadFrame,
+};
+use crate::util::gate::{GateAnalysis, GateSectors, StickGates};
+use crate::util::health::HealthData;
+#[cfg(not(target_os = "linux"))]
+use crate::util::input_wrapper::XInput as Backend;
+use crate::util::input_wrapper::{Buttons, Capabilities, Leds, RawInput, Rumble};
+use crate::util::led::LedInfo;
+use crate::util::mapping::{AppliedMapping, MappingStore, RawJoystick};
 use crate::util::math_util::MathUtil;
+use crate::util::motion::{MotionLog, MotionSample, MotionStats};
+use crate::util::normalize::normalize_i16;
+use crate::util::power::{BatteryHistory, BatteryReport, PowerInfo};
+use crate::util::profile::profile_key;
+use crate::util::settings::DEFAULT_LOG_SIZE;
+use crate::util::snapback::{SnapbackStats, StickSnapback};
+use crate::util::touchpad::{TouchContact, TouchpadLog, TouchpadReport, TouchpadStats};
 use libm::atan2;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
-use std::time::Instant;
+use std::time::{Duration, Instant, SystemTime};
 
-const DEFAULT_LOG_SIZE: usize = 2000;
 const CALCULATE_INTERVAL: usize = 100; // caluculate onece per 100 logs
 const MAX_R: f64 = 32767.0f64; // 最大圆半径
-const DEFAULT_DIR_PRECISION: u32 = 0;
+const NYQUIST_RATIO: f64 = 2.0; // 采样率至少为回报率的 2 倍
+const HIGH_CONFIDENCE_RATIO: f64 = 4.0;
 
 #[derive(Debug)]
 pub struct GamepadState {
-    pub xinput_state: Arc<XInput>,
+    pub xinput_state: Arc<Backend>,
     pub cur_gamepads: Arc<Mutex<HashSet<u32>>>,
     pub memo: Arc<RwLock<HashMap<u32, Memo>>>,
+    /// 新建 Memo 时使用的日志大小
+    pub log_size: AtomicUsize,
+    pub connection: RwLock<HashMap<u32, ConnectionStatus>>,
+    /// 按设备身份键记录的电量历史
+    pub battery_history: RwLock<HashMap<String, BatteryHistory>>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Memo {
     pub polling_rate_log: Vec<PollingRateLog>,
     pub polling_rate_result: PollingRateResult,
-    pub direction_bins: (HashMap<Direction, f32>, HashMap<Direction, f32>),
+    /// 左右摇杆各方向的最大半径，用于平均误差与外框拟合
+    pub direction_bins: (GateSectors, GateSectors),
+    /// 由 direction_bins 拟合的摇杆外框，与回报率结果同时更新
+    pub gates: StickGates,
     pub math_utils: MathUtil,
     pub log_size: usize,
     pub instant: Instant,
+    /// 与 instant 同时刻的系统时间，用于换算内核事件时间戳
+    pub epoch: SystemTime,
+    pub sample_source: SampleSource,
+    /// IMU 上报记录，时间基准与摇杆日志相同
+    pub motion_log: MotionLog,
+    pub touchpad_log: TouchpadLog,
+    /// 按采样时间戳记录的按键状态变化
+    pub button_log: ButtonLog,
+}
+
+impl Default for Memo {
+    fn default() -> Self {
+        Memo::new()
+    }
 }
 
 impl Memo {
     pub fn new() -> Self {
+        Memo::with_log_size(DEFAULT_LOG_SIZE)
+    }
+
+    pub fn with_log_size(log_size: usize) -> Self {
         Memo {
-            polling_rate_log: Vec::with_capacity(DEFAULT_LOG_SIZE),
+            polling_rate_log: Vec::with_capacity(log_size),
             polling_rate_result: PollingRateResult::new(),
-            direction_bins: (HashMap::new(), HashMap::new()),
+            direction_bins: (GateSectors::new(), GateSectors::new()),
+            gates: StickGates::default(),
             math_utils: MathUtil::new(),
-            log_size: DEFAULT_LOG_SIZE,
+            log_size,
             instant: Instant::now(),
+            epoch: SystemTime::now(),
+            sample_source: SampleSource::Unknown,
+            motion_log: MotionLog::new(log_size),
+            touchpad_log: TouchpadLog::new(log_size),
+            button_log: ButtonLog::new(),
         }
     }
 
     pub fn reset(&mut self) {
         self.polling_rate_log.clear();
         self.polling_rate_result = PollingRateResult::new();
-        self.direction_bins = (HashMap::new(), HashMap::new());
+        self.direction_bins = (GateSectors::new(), GateSectors::new());
+        self.gates = StickGates::default();
         self.math_utils = MathUtil::new();
+        self.motion_log.clear();
+        self.touchpad_log.clear();
+        self.button_log.clear();
         self.instant = Instant::now();
+        self.epoch = SystemTime::now();
     }
 }
 
 unsafe impl Send for GamepadState {}
 
+impl Default for GamepadState {
+    fn default() -> Self {
+        GamepadState::new()
+    }
+}
+
 impl GamepadState {
     pub fn new() -> Self {
         GamepadState {
-            xinput_state: Arc::new(XInput::new()),
+            xinput_state: Arc::new(Backend::new()),
             cur_gamepads: Arc::new(Mutex::new(HashSet::with_capacity(10))),
             memo: Arc::new(RwLock::new(HashMap::with_capacity(10))),
+            log_size: AtomicUsize::new(DEFAULT_LOG_SIZE),
+            connection: RwLock::new(HashMap::new()),
+            battery_history: RwLock::new(HashMap::new()),
         }
     }
 
+    fn new_memo(&self) -> Memo {
+        Memo::with_log_size(self.log_size.load(Ordering::SeqCst))
+    }
+
     /// 线程安全地从 XInput 控制器状态构造 GamepadInfo
-    pub fn get_xinput_gamepad(&self, user_index: u32) -> Result<GamepadInfo, String> {
+    pub fn get_xinput_gamepad(&self, user_index: u32) -> Result<GamepadInfo, GamepadError> {
         let xinput_state = &self.xinput_state;
-        let gamepad = if let Ok(_) = xinput_state.update(user_index) {
-            xinput_state.get_controller(user_index).unwrap()
-        } else {
-            return Err(format!(
-                "Failed to get controller for user index: {}",
-                user_index
-            ));
-        };
+        self.track_connection(user_index, xinput_state.update(user_index))?;
+        let gamepad = xinput_state.get_controller(user_index)?;
 
         // 映射按钮
-        let buttons = gamepad
+        let mut buttons = gamepad
             .buttons
             .iter()
             .map(|(k, v)| {
@@ -83,6 +149,7 @@ impl GamepadState {
                         button: k.to_string(),
                         is_pressed: v.is_pressed,
                         value: v.value as f64 / 255.0f64,
+                        transformed: None,
                     },
                 )
             })
@@ -97,29 +164,107 @@ impl GamepadState {
                     k.to_string(),
                     AxisData {
                         axis: k.to_string(),
-                        value: v.value as f64 / 32767.0f64,
+                        value: v.normalized(),
+                        raw: v.raw,
+                        calibrated: None,
+                        transformed: None,
                     },
                 )
             })
             .collect::<HashMap<String, AxisData>>();
 
-        let vendor_id = 0;
-        let product_id = 0;
-        let name = format!("Xinput Controller {}", user_index);
+        let key = profile_key(&gamepad);
+        self.record_battery(&key, &gamepad.power);
+        let (motion, touchpad) = self
+            .memo
+            .read()
+            .ok()
+            .and_then(|memo_map| {
+                let memo = memo_map.get(&user_index)?;
+                Some((memo.motion_log.last(), memo.touchpad_log.last().cloned()))
+            })
+            .unwrap_or_default();
+        // 触摸板按下由触摸板子设备上报，与映射得到的同名按键合并
+        if let Some(report) = &touchpad {
+            let name = Buttons::Touchpad.to_string();
+            let button = buttons.entry(name.clone()).or_insert(ButtonData {
+                button: name,
+                is_pressed: false,
+                value: 0.0,
+                transformed: None,
+            });
+            if report.clicked {
+                button.is_pressed = true;
+                button.value = 1.0;
+            }
+        }
 
         // 构造 GamepadInfo
         Ok(GamepadInfo {
             id: user_index,
-            name,
-            vendor_id: Some(vendor_id),
-            product_id: Some(product_id),
-            guid: "".to_string(),
-            power_info: gamepad.power_info,
+            profile_key: key,
+            name: gamepad.name,
+            vendor_id: gamepad.vendor_id,
+            product_id: gamepad.product_id,
+            guid: gamepad.guid,
+            power: gamepad.power,
+            connection: ConnectionStatus::Connected,
+            motion,
+            touchpad: touchpad.map(|report| report.contacts),
             axes,
             buttons,
         })
     }
 
+    fn record_battery(&self, key: &str, power: &PowerInfo) {
+        if let Ok(mut history) = self.battery_history.write() {
+            history
+                .entry(key.to_string())
+                .or_insert_with(BatteryHistory::new)
+                .record(power);
+        }
+    }
+
+    /// 当前电池状态与历史记录，附带续航估算
+    pub fn get_battery_report(&self, user_index: u32) -> Result<BatteryReport, GamepadError> {
+        self.track_connection(user_index, self.xinput_state.update(user_index))?;
+        let gamepad = self.xinput_state.get_controller(user_index)?;
+        let key = profile_key(&gamepad);
+        self.record_battery(&key, &gamepad.power);
+        let history = self
+            .battery_history
+            .read()
+            .map_err(|_| GamepadError::lock("battery history"))?;
+        Ok(match history.get(&key) {
+            Some(history) => history.report(gamepad.power),
+            None => BatteryHistory::new().report(gamepad.power),
+        })
+    }
+
+    /// 记录一次读取结果对应的连接状态
+    pub fn track_connection<T>(
+        &self,
+        user_index: u32,
+        result: Result<T, GamepadError>,
+    ) -> Result<T, GamepadError> {
+        let status = match &result {
+            Ok(_) => ConnectionStatus::Connected,
+            Err(e) => ConnectionStatus::from_error(e),
+        };
+        if let Ok(mut connection) = self.connection.write() {
+            connection.insert(user_index, status);
+        }
+        result
+    }
+
+    pub fn get_connection(&self, user_index: u32) -> ConnectionStatus {
+        self.connection
+            .read()
+            .ok()
+            .and_then(|connection| connection.get(&user_index).copied())
+            .unwrap_or(ConnectionStatus::Disconnected)
+    }
+
     /// 线程安全地获取当前游戏手柄IDs
     pub fn get_cur_gamepads(&self) -> HashSet<u32> {
         let cur: HashSet<u32> = self.xinput_state.all_device_id().iter().cloned().collect();
@@ -132,24 +277,216 @@ impl GamepadState {
     }
 
     /// 线程安全地记录游戏手柄数据
-    pub fn record(&self, user_index: u32, is_filter_duplicate: bool) -> Result<(), String> {
+    pub fn record(&self, user_index: u32, is_filter_duplicate: bool) -> Result<(), GamepadError> {
         // 获取轴值 - 先更新状态，然后获取轴值
-        let xyxy = if let Ok(_) = self.xinput_state.update(user_index) {
-            self.xinput_state.get_axis_val().unwrap_or((0, 0, 0, 0))
-        } else {
-            return Err("Failed to update XInput state".to_string());
-        };
+        // 断开后不再记录全零状态
+        self.track_connection(user_index, self.xinput_state.update(user_index))?;
+        let xyxy = self.xinput_state.get_axis_val().unwrap_or((0, 0, 0, 0));
+        let pressed = self.pressed_buttons(user_index);
+
+        self.push_log(
+            user_index,
+            |memo| memo.instant.elapsed().as_micros() as u64,
+            xyxy,
+            &pressed,
+            is_filter_duplicate,
+        )
+    }
 
+    #[cfg(target_os = "linux")]
+    fn pressed_buttons(&self, _user_index: u32) -> HashSet<Buttons> {
+        self.xinput_state
+            .get_state()
+            .map(|state| state.pressed)
+            .unwrap_or_default()
+    }
+
+    // 不经过 get_controller，避免调试构建的虚拟手柄以随机按键污染抖动统计
+    #[cfg(not(target_os = "linux"))]
+    fn pressed_buttons(&self, _user_index: u32) -> HashSet<Buttons> {
+        self.xinput_state.pressed_buttons()
+    }
+
+    /// 线程安全地记录一次带内核时间戳的上报（事件驱动采样）
+    pub fn record_report(
+        &self,
+        user_index: u32,
+        time: SystemTime,
+        xyxy: (i16, i16, i16, i16),
+        pressed: &HashSet<Buttons>,
+        is_filter_duplicate: bool,
+    ) -> Result<(), GamepadError> {
+        self.push_log(
+            user_index,
+            |memo| {
+                time.duration_since(memo.epoch)
+                    .unwrap_or_default()
+                    .as_micros() as u64
+            },
+            xyxy,
+            pressed,
+            is_filter_duplicate,
+        )
+    }
+
+    /// 打开事件驱动采样所需的上报流
+    #[cfg(target_os = "linux")]
+    pub fn open_report_stream(&self, user_index: u32) -> Result<EvdevEventStream, GamepadError> {
+        self.xinput_state.open_event_stream(user_index)
+    }
+
+    /// 打开 IMU 子设备的上报流；XInput 不提供体感数据
+    #[cfg(target_os = "linux")]
+    pub fn open_motion_stream(&self, user_index: u32) -> Result<MotionEventStream, GamepadError> {
+        self.xinput_state.open_motion_stream(user_index)
+    }
+
+    /// 线程安全地记录一批 IMU 上报
+    #[cfg(target_os = "linux")]
+    pub fn record_motion(
+        &self,
+        user_index: u32,
+        reports: &[MotionReport],
+    ) -> Result<(), GamepadError> {
+        let mut memo_map = self
+            .memo
+            .write()
+            .map_err(|_| GamepadError::lock("memo map"))?;
+        let memo = memo_map.entry(user_index).or_insert(self.new_memo());
+        for report in reports {
+            memo.motion_log.push(report.to_sample(memo.epoch));
+        }
+        Ok(())
+    }
+
+    /// 读取 IMU 日志与统计（回报率、噪声、零漂）
+    pub fn get_motion_data(&self, user_id: u32) -> Option<(Vec<MotionSample>, MotionStats)> {
+        let memo_map = self.memo.read().ok()?;
+        let motion_log = &memo_map.get(&user_id)?.motion_log;
+        Some((motion_log.samples(), motion_log.stats()))
+    }
+
+    /// 打开触摸板子设备的上报流
+    #[cfg(target_os = "linux")]
+    pub fn open_touchpad_stream(
+        &self,
+        user_index: u32,
+    ) -> Result<TouchpadEventStream, GamepadError> {
+        self.xinput_state.open_touchpad_stream(user_index)
+    }
+
+    /// 线程安全地记录一批触摸板上报
+    #[cfg(target_os = "linux")]
+    pub fn record_touchpad(
+        &self,
+        user_index: u32,
+        frames: &[TouchpadFrame],
+    ) -> Result<(), GamepadError> {
+        let mut memo_map = self
+            .memo
+            .write()
+            .map_err(|_| GamepadError::lock("memo map"))?;
+        let memo = memo_map.entry(user_index).or_insert(self.new_memo());
+        for frame in frames {
+            memo.touchpad_log.push(frame.to_report(memo.epoch));
+        }
+        Ok(())
+    }
+
+    /// 读取触摸板日志与统计（回报率、覆盖率）
+    pub fn get_touchpad_data(&self, user_id: u32) -> Option<(Vec<TouchpadReport>, TouchpadStats)> {
+        let memo_map = self.memo.read().ok()?;
+        let touchpad_log = &memo_map.get(&user_id)?.touchpad_log;
+        Some((touchpad_log.reports(), touchpad_log.stats()))
+    }
+
+    /// 驱动震动马达，强度均为 0 时停止
+    pub fn set_rumble(
+        &self,
+        user_index: u32,
+        low: u16,
+        high: u16,
+        duration: Duration,
+    ) -> Result<(), GamepadError> {
+        self.xinput_state
+            .set_rumble(user_index, low, high, duration)
+    }
+
+    pub fn get_capabilities(&self, user_index: u32) -> Result<DeviceCapabilities, GamepadError> {
+        self.xinput_state.get_capabilities(user_index)
+    }
+
+    pub fn get_leds(&self, user_index: u32) -> Result<LedInfo, GamepadError> {
+        self.xinput_state.get_leds(user_index)
+    }
+
+    pub fn set_player_led(&self, user_index: u32, player: u8) -> Result<(), GamepadError> {
+        self.xinput_state.set_player_led(user_index, player)
+    }
+
+    pub fn set_lightbar(
+        &self,
+        user_index: u32,
+        red: u8,
+        green: u8,
+        blue: u8,
+    ) -> Result<(), GamepadError> {
+        self.xinput_state.set_lightbar(user_index, red, green, blue)
+    }
+
+    /// 设置 GameControllerDB 映射来源；XInput 布局固定，不使用映射
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    pub fn set_mapping_store(&self, mapping_store: Arc<MappingStore>) {
+        #[cfg(target_os = "linux")]
+        self.xinput_state.set_mapping_store(mapping_store);
+    }
+
+    /// 映射变化后让已打开的设备重新查找映射
+    pub fn reload_mappings(&self) {
+        #[cfg(target_os = "linux")]
+        self.xinput_state.reload_mappings();
+    }
+
+    pub fn get_applied_mapping(&self, user_index: u32) -> Result<AppliedMapping, GamepadError> {
+        #[cfg(target_os = "linux")]
+        return self.xinput_state.applied_mapping(user_index);
+        #[cfg(not(target_os = "linux"))]
+        {
+            let gamepad = self.xinput_state.get_controller(user_index)?;
+            Ok(AppliedMapping::native(&gamepad))
+        }
+    }
+
+    /// 读取未经映射的原始输入；XInput 只提供标准布局，无法读取
+    #[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+    pub fn read_raw_joystick(&self, user_index: u32) -> Result<RawJoystick, GamepadError> {
+        #[cfg(target_os = "linux")]
+        return self.xinput_state.read_raw(user_index);
+        #[cfg(not(target_os = "linux"))]
+        Err(GamepadError::BackendUnavailable(
+            "Raw input is not available for XInput devices".to_string(),
+        ))
+    }
+
+    fn push_log(
+        &self,
+        user_index: u32,
+        timestamp: impl FnOnce(&Memo) -> u64,
+        xyxy: (i16, i16, i16, i16),
+        pressed: &HashSet<Buttons>,
+        is_filter_duplicate: bool,
+    ) -> Result<(), GamepadError> {
         // 记录数据
         if let Ok(mut memo_map) = self.memo.write() {
-            let memo = memo_map.entry(user_index).or_insert(Memo::new());
-            let logs = &mut memo.polling_rate_log;
-            let direction_log = &mut memo.direction_bins;
-
+            let memo = memo_map.entry(user_index).or_insert(self.new_memo());
             let log = PollingRateLog {
-                timestamp: memo.instant.elapsed().as_micros() as u64,
+                timestamp: timestamp(memo),
                 xyxy,
             };
+            // 按键变化在摇杆去重之前记录
+            memo.button_log.record(log.timestamp, pressed);
+            let logs = &mut memo.polling_rate_log;
+            let direction_log = &mut memo.direction_bins;
 
             #[cfg(debug_assertions)]
             {
@@ -165,22 +502,12 @@ impl GamepadState {
             }
 
             logs.push(log);
-            let theta_l =
-                Direction::new(atan2(xyxy.1 as f64, xyxy.0 as f64), DEFAULT_DIR_PRECISION);
-            let theta_r =
-                Direction::new(atan2(xyxy.3 as f64, xyxy.2 as f64), DEFAULT_DIR_PRECISION);
-            let r_l = ((xyxy.0 as f64).powi(2) + (xyxy.1 as f64).powi(2)).sqrt() as f32;
-            let r_r = ((xyxy.2 as f64).powi(2) + (xyxy.3 as f64).powi(2)).sqrt() as f32;
-            direction_log
-                .0
-                .entry(theta_l)
-                .and_modify(|r| *r = r_l.max(*r))
-                .or_insert(r_l);
-            direction_log
-                .1
-                .entry(theta_r)
-                .and_modify(|r| *r = r_r.max(*r))
-                .or_insert(r_r);
+            let angle_l = atan2(xyxy.1 as f64, xyxy.0 as f64);
+            let angle_r = atan2(xyxy.3 as f64, xyxy.2 as f64);
+            let r_l = ((xyxy.0 as f64).powi(2) + (xyxy.1 as f64).powi(2)).sqrt();
+            let r_r = ((xyxy.2 as f64).powi(2) + (xyxy.3 as f64).powi(2)).sqrt();
+            direction_log.0.record(angle_l, r_l / MAX_R);
+            direction_log.1.record(angle_r, r_r / MAX_R);
 
             // 限制日志长度
             if logs.len() > memo.log_size {
@@ -195,15 +522,28 @@ impl GamepadState {
             return Ok(());
         }
 
-        Err("Failed to lock memo map".to_string())
+        Err(GamepadError::lock("memo map"))
+    }
+
+    /// 线程安全地更新采样方式（由采样线程定期同步实际采样率）
+    pub fn set_sample_source(&self, user_index: u32, source: SampleSource) {
+        if let Ok(mut memo_map) = self.memo.write() {
+            memo_map
+                .entry(user_index)
+                .or_insert(self.new_memo())
+                .sample_source = source;
+        }
     }
 
     /// 线程安全地设置日志大小
     pub fn set_log_size(&self, log_size: usize) {
+        self.log_size.store(log_size, Ordering::SeqCst);
         if let Ok(mut memo_map) = self.memo.write() {
             memo_map.iter_mut().for_each(|(_, memo)| {
                 memo.log_size = log_size;
                 memo.polling_rate_log = Vec::with_capacity(log_size);
+                memo.motion_log.set_log_size(log_size);
+                memo.touchpad_log.set_log_size(log_size);
             });
         }
     }
@@ -217,6 +557,68 @@ impl GamepadState {
         }
     }
 
+    /// 回报率日志中最新样本的时间戳，用于判断日志是否有更新
+    pub fn latest_log_timestamp(&self, user_id: u32) -> Option<u64> {
+        let memo_map = self.memo.read().ok()?;
+        Some(memo_map.get(&user_id)?.polling_rate_log.last()?.timestamp)
+    }
+
+    /// 回报率日志中时间戳晚于 since 的摇杆样本，已归一化到 [-1, 1]
+    pub fn axis_samples_since(&self, user_id: u32, since: u64) -> Vec<(u64, [f64; 4])> {
+        let Ok(memo_map) = self.memo.read() else {
+            return Vec::new();
+        };
+        let Some(memo) = memo_map.get(&user_id) else {
+            return Vec::new();
+        };
+        memo.polling_rate_log
+            .iter()
+            .filter(|log| log.timestamp > since)
+            .map(|log| {
+                let (lx, ly, rx, ry) = log.xyxy;
+                (log.timestamp, [lx, ly, rx, ry].map(normalize_i16))
+            })
+            .collect()
+    }
+
+    /// 从回报率日志中检测摇杆松开回中的过冲、振荡与稳定时间
+    pub fn get_snapback(&self, user_id: u32) -> Option<StickSnapback> {
+        let memo_map = self.memo.read().ok()?;
+        let logs = &memo_map.get(&user_id)?.polling_rate_log;
+        let stick = |axes: fn(&PollingRateLog) -> (i16, i16)| {
+            let samples: Vec<(u64, f64, f64)> = logs
+                .iter()
+                .map(|log| {
+                    let (x, y) = axes(log);
+                    (log.timestamp, normalize_i16(x), normalize_i16(y))
+                })
+                .collect();
+            SnapbackStats::analyze(&samples)
+        };
+        Some(StickSnapback {
+            left: stick(|log| (log.xyxy.0, log.xyxy.1)),
+            right: stick(|log| (log.xyxy.2, log.xyxy.3)),
+        })
+    }
+
+    /// 健康报告所需的摇杆样本、回报率结果、外框分析与按键抖动统计
+    pub fn get_health_data(&self, user_id: u32) -> Option<HealthData> {
+        let samples = self.axis_samples_since(user_id, 0);
+        let memo_map = self.memo.read().ok()?;
+        let memo = memo_map.get(&user_id)?;
+        Some(HealthData {
+            samples,
+            polling_rate: memo.polling_rate_result.clone(),
+            gates: memo.gates.clone(),
+            chatter: memo.button_log.stats(),
+        })
+    }
+
+    pub fn get_gate_analysis(&self, user_id: u32) -> Option<StickGates> {
+        let memo_map = self.memo.read().ok()?;
+        memo_map.get(&user_id).map(|memo| memo.gates.clone())
+    }
+
     /// 无锁读取轮询率数据（使用读锁，不阻塞写入）
     pub fn get_polling_data(
         &self,
@@ -243,7 +645,7 @@ pub fn get_performance_stat(memo: &mut Memo) {
             &logs
                 .iter()
                 .map(|log| (log.timestamp as i64, &log.xyxy))
-                .collect(),
+                .collect::<Vec<_>>(),
         )
         .unwrap();
     memo.polling_rate_result = PollingRateResult {
@@ -253,27 +655,29 @@ pub fn get_performance_stat(memo: &mut Memo) {
         avg_interval: result.3,
         avg_error_l: calc_avg_error(&memo.direction_bins.0),
         avg_error_r: calc_avg_error(&memo.direction_bins.1),
+        measurement_confidence: MeasurementConfidence::evaluate(memo.sample_source, result.0),
+    };
+    memo.gates = StickGates {
+        left: GateAnalysis::from_sectors(&memo.direction_bins.0),
+        right: GateAnalysis::from_sectors(&memo.direction_bins.1),
     };
 }
 
-fn calc_avg_error<T>(dir_bin: &HashMap<T, f32>) -> f64 {
-    let length = dir_bin.len();
-    let n = if length == 0 { 1 } else { length } as f64;
-    let sum = dir_bin.iter().map(|(_, v)| v).sum::<f32>() as f64;
-    (1.0f64 - ((sum / n) / MAX_R)).abs()
+fn calc_avg_error(dir_bin: &GateSectors) -> f64 {
+    (1.0f64 - dir_bin.mean_radius()).abs()
 }
 
-pub fn polling_rate_log_to_output_log(logs: &Vec<PollingRateLog>) -> Vec<OutputLog> {
+pub fn polling_rate_log_to_output_log(logs: &[PollingRateLog]) -> Vec<OutputLog> {
     logs.iter()
         .map(|log| {
             let xyxy = log.xyxy;
             OutputLog {
                 timestamp: log.timestamp,
                 xyxy: (
-                    xyxy.0 as f64 / MAX_R,
-                    xyxy.1 as f64 / MAX_R,
-                    xyxy.2 as f64 / MAX_R,
-                    xyxy.3 as f64 / MAX_R,
+                    normalize_i16(xyxy.0),
+                    normalize_i16(xyxy.1),
+                    normalize_i16(xyxy.2),
+                    normalize_i16(xyxy.3),
                 ),
             }
         })
@@ -283,7 +687,14 @@ pub fn polling_rate_log_to_output_log(logs: &Vec<PollingRateLog>) -> Vec<OutputL
 #[derive(Serialize, Debug, Deserialize, Clone)]
 pub struct AxisData {
     pub axis: String,
+    /// 按设备原生范围归一化到 [-1, 1]，向上、向右为正
     pub value: f64,
+    /// 设备上报的原生值
+    pub raw: i32,
+    /// 按设备配置校准后的值，未启用校准时为空
+    pub calibrated: Option<f64>,
+    /// 经过死区与响应曲线变换后的值，未启用变换时为空
+    pub transformed: Option<f64>,
 }
 
 #[derive(Serialize, Debug, Deserialize, Clone)]
@@ -291,26 +702,79 @@ pub struct ButtonData {
     pub button: String,
     pub is_pressed: bool,
     pub value: f64,
+    /// 扳机经过死区与响应曲线变换后的值
+    pub transformed: Option<f64>,
 }
 
 #[derive(Serialize, Debug, Deserialize, Clone)]
 pub struct GamepadInfo {
     pub id: u32,
+    /// 设备身份键，对应保存的手柄配置
+    pub profile_key: String,
     pub name: String,
     pub vendor_id: Option<u16>,
     pub product_id: Option<u16>,
     pub guid: String,
-    pub power_info: String,
+    pub power: PowerInfo,
+    pub connection: ConnectionStatus,
+    /// 最近一次 IMU 上报，没有体感传感器时为 None
+    pub motion: Option<MotionSample>,
+    /// 最近一次触摸板上报的触点，没有触摸板时为 None
+    pub touchpad: Option<Vec<TouchContact>>,
     pub axes: HashMap<String, AxisData>,
     pub buttons: HashMap<String, ButtonData>,
 }
 
+impl GamepadInfo {
+    /// 设备不可用时发送给前端的占位信息
+    pub fn unavailable(id: u32, connection: ConnectionStatus) -> Self {
+        GamepadInfo {
+            id,
+            profile_key: String::new(),
+            name: String::new(),
+            vendor_id: None,
+            product_id: None,
+            guid: String::new(),
+            power: PowerInfo::unknown(),
+            connection,
+            motion: None,
+            touchpad: None,
+            axes: HashMap::new(),
+            buttons: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionStatus {
+    Connected,
+    Disconnected,
+    /// 设备存在但读取失败（如无权限）
+    Error,
+}
+
+impl ConnectionStatus {
+    pub fn from_error(e: &GamepadError) -> Self {
+        match e {
+            GamepadError::DeviceNotConnected(_) => ConnectionStatus::Disconnected,
+            _ => ConnectionStatus::Error,
+        }
+    }
+}
+
 #[derive(Serialize, Debug, Deserialize, Clone)]
 pub struct PollingRateLog {
     pub timestamp: u64,
     pub xyxy: (i16, i16, i16, i16),
 }
 
+impl Default for PollingRateLog {
+    fn default() -> Self {
+        PollingRateLog::new()
+    }
+}
+
 impl PollingRateLog {
     pub fn new() -> Self {
         PollingRateLog {
@@ -334,6 +798,13 @@ pub struct PollingRateResult {
     pub avg_interval: f64,
     pub avg_error_l: f64,
     pub avg_error_r: f64,
+    pub measurement_confidence: MeasurementConfidence,
+}
+
+impl Default for PollingRateResult {
+    fn default() -> Self {
+        PollingRateResult::new()
+    }
 }
 
 impl PollingRateResult {
@@ -345,29 +816,51 @@ impl PollingRateResult {
             avg_interval: 0.0,
             avg_error_l: 0.0,
             avg_error_r: 0.0,
+            measurement_confidence: MeasurementConfidence::Unknown,
         }
     }
 }
 
-#[derive(Hash, Eq, PartialEq, Debug, Clone)]
-pub struct Direction {
-    int_part: i64,
-    frac_part: u64,
-    precision: u32, // 记录精度方便后续比较和输出
+/// 采样方式，用于评估测量可信度
+#[derive(Debug, Clone, Copy)]
+pub enum SampleSource {
+    Unknown,
+    /// 定时轮询，附带实际达到的采样率 (Hz)
+    Polling(f64),
+    /// 事件驱动，每次上报都带内核时间戳
+    Event,
+}
+
+/// 采样率相对测得回报率是否足够（类 Nyquist 判据）
+#[derive(Serialize, Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MeasurementConfidence {
+    Unknown,
+    /// 采样率不足回报率的 2 倍，测得值可能受本工具限制
+    Low,
+    Medium,
+    High,
+    /// 事件驱动采样，间隔为精确值
+    Exact,
 }
 
-impl Direction {
-    /// 使用给定精度（小数位数）拆分 f64 值的整数和小数部分
-    fn new(val: f64, precision: u32) -> Direction {
-        let int_part = val.trunc() as i64;
-        let factor = 10u64.pow(precision);
-        // 计算绝对值的小数部分，四舍五入再转为整数
-        let frac_part = (val.fract().abs() * factor as f64).round() as u64;
-
-        Direction {
-            int_part,
-            frac_part,
-            precision,
+impl MeasurementConfidence {
+    pub fn evaluate(source: SampleSource, controller_rate: f64) -> Self {
+        match source {
+            SampleSource::Event => MeasurementConfidence::Exact,
+            SampleSource::Polling(sampler_rate)
+                if controller_rate.is_finite() && controller_rate > 0.0 =>
+            {
+                let ratio = sampler_rate / controller_rate;
+                if ratio >= HIGH_CONFIDENCE_RATIO {
+                    MeasurementConfidence::High
+                } else if ratio >= NYQUIST_RATIO {
+                    MeasurementConfidence::Medium
+                } else {
+                    MeasurementConfidence::Low
+                }
+            }
+            _ => MeasurementConfidence::Unknown,
         }
     }
 }
@@ -0,0 +1,278 @@
+use serde::{Deserialize, Serialize};
+use std::f64::consts::{FRAC_PI_4, FRAC_PI_8, PI, TAU};
+
+const SECTOR_COUNT: usize = 72; // 每 5° 一个扇区
+const MIN_EDGE_RADIUS: f64 = 0.3; // 小于该半径的扇区认为没有推到外框
+const MIN_COVERAGE: f64 = 0.75; // 覆盖的扇区比例不足时不判断形状
+const ROUNDED_SQUARE_EXPONENT: f64 = 4.0; // 超椭圆 |x|^n + |y|^n = 1
+
+/// 摇杆外框（物理限位或固件限幅）的形状
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GateShape {
+    Circle,
+    /// 边与轴平行，对角方向最远
+    Square,
+    /// 八个顶点位于正方向与对角方向（如 GameCube 手柄）
+    Octagon,
+    RoundedSquare,
+}
+
+impl GateShape {
+    const ALL: [GateShape; 4] = [
+        GateShape::Circle,
+        GateShape::Square,
+        GateShape::Octagon,
+        GateShape::RoundedSquare,
+    ];
+
+    /// 正方向半径为 1 时 angle 方向的半径
+    pub fn radius(&self, angle: f64) -> f64 {
+        let (c, s) = (angle.cos().abs(), angle.sin().abs());
+        match self {
+            GateShape::Circle => 1.0,
+            GateShape::Square => 1.0 / c.max(s),
+            GateShape::Octagon => {
+                let offset = angle.rem_euclid(FRAC_PI_4) - FRAC_PI_8;
+                FRAC_PI_8.cos() / offset.cos()
+            }
+            GateShape::RoundedSquare => (c.powf(ROUNDED_SQUARE_EXPONENT)
+                + s.powf(ROUNDED_SQUARE_EXPONENT))
+            .powf(-1.0 / ROUNDED_SQUARE_EXPONENT),
+        }
+    }
+}
+
+/// 单一形状的拟合结果
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GateFit {
+    pub shape: GateShape,
+    /// 拟合得到的正方向半径（归一化单位）
+    pub scale: f64,
+    /// 残差均方根与平均半径之比
+    pub rms_error: f64,
+    /// 1 - rms_error，越接近 1 越吻合
+    pub score: f64,
+}
+
+/// 单个摇杆的外框分析，轮廓点为 [-1, 1] 坐标，按角度排列
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GateAnalysis {
+    /// 推到外框的扇区比例
+    pub coverage: f64,
+    /// 覆盖不足时为空
+    pub shape: Option<GateShape>,
+    pub score: f64,
+    /// 各形状的拟合结果，按得分从高到低
+    pub fits: Vec<GateFit>,
+    /// 各扇区测得的最大半径
+    pub measured: Vec<(f64, f64)>,
+    /// 最佳拟合形状的理想轮廓
+    pub fitted: Vec<(f64, f64)>,
+}
+
+/// 每个扇区内推到的最大归一化半径，未经过的扇区为空
+#[derive(Debug, Clone)]
+pub struct GateSectors {
+    radii: [Option<f64>; SECTOR_COUNT],
+}
+
+impl GateSectors {
+    pub fn new() -> Self {
+        GateSectors {
+            radii: [None; SECTOR_COUNT],
+        }
+    }
+
+    /// angle 为 atan2 得到的角度 (rad)，radius 为归一化半径
+    pub fn record(&mut self, angle: f64, radius: f64) {
+        let index = ((angle + PI).rem_euclid(TAU) / TAU * SECTOR_COUNT as f64) as usize;
+        let sector = &mut self.radii[index.min(SECTOR_COUNT - 1)];
+        *sector = Some(sector.map_or(radius, |r| r.max(radius)));
+    }
+
+    /// 经过的扇区最大半径的平均值，没有记录时为 0
+    pub fn mean_radius(&self) -> f64 {
+        let (sum, n) = self
+            .radii
+            .iter()
+            .flatten()
+            .fold((0.0, 0usize), |(sum, n), r| (sum + r, n + 1));
+        sum / n.max(1) as f64
+    }
+}
+
+impl Default for GateSectors {
+    fn default() -> Self {
+        GateSectors::new()
+    }
+}
+
+impl GateAnalysis {
+    pub fn from_sectors(sectors: &GateSectors) -> Self {
+        let samples: Vec<(f64, f64)> = sectors
+            .radii
+            .iter()
+            .enumerate()
+            .filter_map(|(index, radius)| Some((sector_angle(index), (*radius)?)))
+            .filter(|(_, radius)| *radius >= MIN_EDGE_RADIUS)
+            .collect();
+        let coverage = samples.len() as f64 / SECTOR_COUNT as f64;
+        let measured = samples
+            .iter()
+            .map(|(angle, radius)| (angle.cos() * radius, angle.sin() * radius))
+            .collect();
+        if coverage < MIN_COVERAGE {
+            return GateAnalysis {
+                coverage,
+                measured,
+                ..GateAnalysis::default()
+            };
+        }
+
+        let mut fits: Vec<GateFit> = GateShape::ALL
+            .iter()
+            .map(|shape| fit(*shape, &samples))
+            .collect();
+        fits.sort_by(|a, b| b.score.total_cmp(&a.score));
+        let best = &fits[0];
+        let fitted = (0..SECTOR_COUNT)
+            .map(|index| {
+                let angle = sector_angle(index);
+                let radius = best.scale * best.shape.radius(angle);
+                (angle.cos() * radius, angle.sin() * radius)
+            })
+            .collect();
+        GateAnalysis {
+            coverage,
+            shape: Some(best.shape),
+            score: best.score,
+            measured,
+            fitted,
+            fits,
+        }
+    }
+}
+
+fn sector_angle(index: usize) -> f64 {
+    (index as f64 + 0.5) / SECTOR_COUNT as f64 * TAU - PI
+}
+
+/// 最小二乘求缩放：scale = Σ(r·f) / Σ(f²)
+fn fit(shape: GateShape, samples: &[(f64, f64)]) -> GateFit {
+    let (mut rf, mut ff, mut sum) = (0.0, 0.0, 0.0);
+    for (angle, radius) in samples {
+        let f = shape.radius(*angle);
+        rf += radius * f;
+        ff += f * f;
+        sum += radius;
+    }
+    let scale = if ff > 0.0 { rf / ff } else { 0.0 };
+    let n = samples.len().max(1) as f64;
+    let residual = samples
+        .iter()
+        .map(|(angle, radius)| (radius - scale * shape.radius(*angle)).powi(2))
+        .sum::<f64>();
+    let mean = sum / n;
+    let rms_error = if mean > 0.0 {
+        (residual / n).sqrt() / mean
+    } else {
+        1.0
+    };
+    GateFit {
+        shape,
+        scale,
+        rms_error,
+        score: (1.0 - rms_error).max(0.0),
+    }
+}
+
+/// 左右摇杆的外框分析（"gate_analysis" 事件）
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct StickGates {
+    pub left: GateAnalysis,
+    pub right: GateAnalysis,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, SQRT_2};
+
+    const EPSILON: f64 = 1e-9;
+
+    /// 沿外框转一圈，每 0.5° 记录一次
+    fn sweep(shape: GateShape, scale: f64, turns: f64) -> GateSectors {
+        let mut sectors = GateSectors::new();
+        let steps = (720.0 * turns) as usize;
+        for i in 0..steps {
+            let angle = i as f64 / 720.0 * TAU - PI;
+            sectors.record(angle, scale * shape.radius(angle));
+        }
+        sectors
+    }
+
+    #[test]
+    fn radius_on_cardinal_and_diagonal() {
+        let cases = [
+            (GateShape::Circle, 1.0, 1.0),
+            (GateShape::Square, 1.0, SQRT_2),
+            (GateShape::Octagon, 1.0, 1.0),
+            (GateShape::RoundedSquare, 1.0, 0.5f64.powf(-0.25)),
+        ];
+        for (shape, cardinal, diagonal) in cases {
+            for k in 0..4 {
+                let angle = k as f64 * FRAC_PI_2 - PI;
+                assert!(
+                    (shape.radius(angle) - cardinal).abs() < EPSILON,
+                    "{:?}",
+                    shape
+                );
+                let angle = angle + FRAC_PI_4;
+                assert!(
+                    (shape.radius(angle) - diagonal).abs() < EPSILON,
+                    "{:?}",
+                    shape
+                );
+            }
+        }
+        // 八边形的边中点在 22.5° 方向
+        assert!((GateShape::Octagon.radius(FRAC_PI_8) - FRAC_PI_8.cos()).abs() < EPSILON);
+    }
+
+    #[test]
+    fn fit_picks_the_swept_shape() {
+        for shape in GateShape::ALL {
+            let analysis = GateAnalysis::from_sectors(&sweep(shape, 0.9, 1.0));
+            assert_eq!(analysis.shape, Some(shape));
+            assert_eq!(analysis.coverage, 1.0);
+            assert_eq!(analysis.fits.len(), GateShape::ALL.len());
+            assert!(
+                (analysis.fits[0].scale - 0.9).abs() < 0.02,
+                "{:?}",
+                analysis.fits[0]
+            );
+            assert!(analysis.score > 0.98);
+            assert_eq!(analysis.fitted.len(), SECTOR_COUNT);
+        }
+    }
+
+    #[test]
+    fn partial_sweep_has_no_shape() {
+        let analysis = GateAnalysis::from_sectors(&sweep(GateShape::Circle, 1.0, 0.5));
+        assert!((analysis.coverage - 0.5).abs() < EPSILON);
+        assert_eq!(analysis.shape, None);
+        assert!(analysis.fits.is_empty());
+        assert_eq!(analysis.measured.len(), SECTOR_COUNT / 2);
+    }
+
+    #[test]
+    fn mean_radius_of_visited_sectors() {
+        let mut sectors = GateSectors::new();
+        assert_eq!(sectors.mean_radius(), 0.0);
+        sectors.record(0.0, 0.8);
+        sectors.record(0.0, 0.6);
+        sectors.record(FRAC_PI_2, 1.0);
+        assert!((sectors.mean_radius() - 0.9).abs() < EPSILON);
+    }
+}
@@ -0,0 +1,462 @@
+use crate::util::chatter::ButtonChatter;
+use crate::util::error::GamepadError;
+use crate::util::gamepad_util::{GamepadInfo, PollingRateResult};
+use crate::util::gate::{GateAnalysis, StickGates};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MIN_INTERVALS: usize = 100;
+const MAX_GAP_RATIO: f64 = 4.0; // 超过中位间隔该倍数的间隔来自去重（摇杆未动），不计入抖动
+const REST_RADIUS: f64 = 0.2; // 该半径内视为松开摇杆
+const MIN_REST_TIME: u64 = 500_000; // us, 静止时长不足时无法判断漂移与噪声
+
+/// 指标的警告与不合格阈值，数值越大越差
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Threshold {
+    pub warn: f64,
+    pub fail: f64,
+}
+
+impl Threshold {
+    const fn new(warn: f64, fail: f64) -> Self {
+        Threshold { warn, fail }
+    }
+
+    fn status(&self, value: Option<f64>) -> MetricStatus {
+        match value {
+            None => MetricStatus::Unknown,
+            Some(value) if value >= self.fail => MetricStatus::Fail,
+            Some(value) if value >= self.warn => MetricStatus::Warn,
+            Some(_) => MetricStatus::Pass,
+        }
+    }
+}
+
+/// 各项指标的阈值，保存在设置中
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct HealthThresholds {
+    /// 上报间隔的离散程度：(P90 - P10) / 中位间隔
+    pub polling_jitter: Threshold,
+    /// 外圈平均半径与 1 的偏差（avg_error_l / avg_error_r）
+    pub circularity_error: Threshold,
+    /// 松开时静止位置与中心的距离
+    pub drift: Threshold,
+    /// 松开时静止位置的标准差
+    pub noise: Threshold,
+    /// 离开恰好为 0 的中心时第一个非零样本的半径（固件死区）
+    pub inner_deadzone: Threshold,
+    /// 四个正方向中最短行程与 1 的差（外死区/行程不足）
+    pub range_loss: Threshold,
+    /// 每次按下平均出现的抖动次数，取最差的按键
+    pub button_chatter: Threshold,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        HealthThresholds {
+            polling_jitter: Threshold::new(0.25, 0.5),
+            circularity_error: Threshold::new(0.1, 0.2),
+            drift: Threshold::new(0.03, 0.08),
+            noise: Threshold::new(0.005, 0.02),
+            inner_deadzone: Threshold::new(0.1, 0.2),
+            range_loss: Threshold::new(0.05, 0.1),
+            button_chatter: Threshold::new(0.01, 0.05),
+        }
+    }
+}
+
+impl HealthThresholds {
+    pub fn validate(&self) -> Result<(), GamepadError> {
+        let thresholds = [
+            ("polling_jitter", self.polling_jitter),
+            ("circularity_error", self.circularity_error),
+            ("drift", self.drift),
+            ("noise", self.noise),
+            ("inner_deadzone", self.inner_deadzone),
+            ("range_loss", self.range_loss),
+            ("button_chatter", self.button_chatter),
+        ];
+        for (name, threshold) in thresholds {
+            if !(threshold.warn >= 0.0
+                && threshold.warn <= threshold.fail
+                && threshold.fail.is_finite())
+            {
+                return Err(GamepadError::invalid(format!(
+                    "Threshold {} must satisfy 0 <= warn <= fail, got {} and {}",
+                    name, threshold.warn, threshold.fail
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// 数据不足（如没有转动摇杆、没有松开摇杆）
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grade {
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+impl Grade {
+    /// 有不合格指标时最高为 C
+    fn from_score(score: f64, passed: bool) -> Self {
+        match score {
+            s if s >= 0.95 && passed => Grade::A,
+            s if s >= 0.85 && passed => Grade::B,
+            s if s >= 0.7 => Grade::C,
+            s if s >= 0.5 => Grade::D,
+            _ => Grade::F,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HealthMetric {
+    pub metric: String,
+    /// 摇杆指标为 "left"/"right"
+    pub target: Option<String>,
+    pub value: Option<f64>,
+    pub threshold: Threshold,
+    pub status: MetricStatus,
+}
+
+/// 评估所需的记录数据
+#[derive(Debug, Clone)]
+pub struct HealthData {
+    /// 回报率日志中归一化后的 xyxy
+    pub samples: Vec<(u64, [f64; 4])>,
+    pub polling_rate: PollingRateResult,
+    pub gates: StickGates,
+    pub chatter: Vec<ButtonChatter>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HealthReport {
+    /// unix 时间戳 (s)
+    pub timestamp: u64,
+    pub device_name: String,
+    pub profile_key: String,
+    pub vendor_id: Option<u16>,
+    pub product_id: Option<u16>,
+    pub polling_rate: PollingRateResult,
+    pub metrics: Vec<HealthMetric>,
+    pub chatter: Vec<ButtonChatter>,
+    /// 已知指标的平均得分：通过 1，警告 0.5，不合格 0
+    pub score: f64,
+    /// 没有可评估的指标时为空
+    pub grade: Option<Grade>,
+    /// 没有不合格的指标
+    pub passed: bool,
+}
+
+impl HealthReport {
+    pub fn evaluate(
+        gamepad: &GamepadInfo,
+        data: HealthData,
+        thresholds: &HealthThresholds,
+    ) -> Self {
+        let mut metrics = Vec::new();
+        let mut push =
+            |metric: &str, target: Option<&str>, value: Option<f64>, threshold: Threshold| {
+                metrics.push(HealthMetric {
+                    metric: metric.to_string(),
+                    target: target.map(str::to_string),
+                    value,
+                    threshold,
+                    status: threshold.status(value),
+                });
+            };
+
+        push(
+            "polling_jitter",
+            None,
+            polling_jitter(&data.samples),
+            thresholds.polling_jitter,
+        );
+        let sticks = [
+            ("left", 0, &data.gates.left, data.polling_rate.avg_error_l),
+            ("right", 2, &data.gates.right, data.polling_rate.avg_error_r),
+        ];
+        for (target, offset, gate, circularity_error) in sticks {
+            let stick: Vec<(u64, f64, f64)> = data
+                .samples
+                .iter()
+                .map(|(t, xyxy)| (*t, xyxy[offset], xyxy[offset + 1]))
+                .collect();
+            let swept = gate.shape.is_some();
+            let rest = rest_stats(&stick);
+            push(
+                "circularity_error",
+                Some(target),
+                swept.then_some(circularity_error),
+                thresholds.circularity_error,
+            );
+            push(
+                "drift",
+                Some(target),
+                rest.map(|(drift, _)| drift),
+                thresholds.drift,
+            );
+            push(
+                "noise",
+                Some(target),
+                rest.map(|(_, noise)| noise),
+                thresholds.noise,
+            );
+            push(
+                "inner_deadzone",
+                Some(target),
+                inner_deadzone(&stick),
+                thresholds.inner_deadzone,
+            );
+            push(
+                "range_loss",
+                Some(target),
+                range_loss(&stick, gate),
+                thresholds.range_loss,
+            );
+        }
+        push(
+            "button_chatter",
+            None,
+            data.chatter
+                .iter()
+                .filter(|chatter| chatter.presses > 0)
+                .map(ButtonChatter::ratio)
+                .reduce(f64::max),
+            thresholds.button_chatter,
+        );
+
+        let known: Vec<MetricStatus> = metrics
+            .iter()
+            .map(|metric| metric.status)
+            .filter(|status| *status != MetricStatus::Unknown)
+            .collect();
+        let score = if known.is_empty() {
+            0.0
+        } else {
+            known
+                .iter()
+                .map(|status| match status {
+                    MetricStatus::Pass => 1.0,
+                    MetricStatus::Warn => 0.5,
+                    _ => 0.0,
+                })
+                .sum::<f64>()
+                / known.len() as f64
+        };
+        let passed = !known.contains(&MetricStatus::Fail);
+        let grade = (!known.is_empty()).then_some(Grade::from_score(score, passed));
+
+        HealthReport {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            device_name: gamepad.name.clone(),
+            profile_key: gamepad.profile_key.clone(),
+            vendor_id: gamepad.vendor_id,
+            product_id: gamepad.product_id,
+            polling_rate: data.polling_rate,
+            metrics,
+            chatter: data.chatter,
+            score,
+            grade,
+            passed,
+        }
+    }
+}
+
+/// 排除去重产生的长间隔后，上报间隔的 (P90 - P10) / 中位数
+fn polling_jitter(samples: &[(u64, [f64; 4])]) -> Option<f64> {
+    let mut intervals: Vec<u64> = samples
+        .windows(2)
+        .map(|w| w[1].0.saturating_sub(w[0].0))
+        .filter(|interval| *interval > 0)
+        .collect();
+    intervals.sort_unstable();
+    let median = *intervals.get(intervals.len() / 2)? as f64;
+    intervals.retain(|interval| (*interval as f64) <= median * MAX_GAP_RATIO);
+    if intervals.len() < MIN_INTERVALS {
+        return None;
+    }
+    let percentile = |p: f64| intervals[((intervals.len() - 1) as f64 * p) as usize] as f64;
+    Some((percentile(0.9) - percentile(0.1)) / median)
+}
+
+/// 松开时的静止位置偏移与标准差；去重后的日志按保持时长加权
+fn rest_stats(stick: &[(u64, f64, f64)]) -> Option<(f64, f64)> {
+    let rest: Vec<(f64, f64, f64)> = stick
+        .windows(2)
+        .filter(|w| w[0].1.hypot(w[0].2) <= REST_RADIUS)
+        .map(|w| ((w[1].0 - w[0].0) as f64, w[0].1, w[0].2))
+        .collect();
+    let total: f64 = rest.iter().map(|(weight, _, _)| weight).sum();
+    if total < MIN_REST_TIME as f64 {
+        return None;
+    }
+    let mean_x = rest.iter().map(|(w, x, _)| w * x).sum::<f64>() / total;
+    let mean_y = rest.iter().map(|(w, _, y)| w * y).sum::<f64>() / total;
+    let variance = rest
+        .iter()
+        .map(|(w, x, y)| w * ((x - mean_x).powi(2) + (y - mean_y).powi(2)))
+        .sum::<f64>()
+        / total;
+    Some((mean_x.hypot(mean_y), variance.sqrt()))
+}
+
+/// 固件死区内上报恰好为 0，离开死区时第一个非零样本的半径即为死区边缘；
+/// 取每次离开中心时的最小值，从未上报过 0 的摇杆未知
+fn inner_deadzone(stick: &[(u64, f64, f64)]) -> Option<f64> {
+    stick
+        .windows(2)
+        .map(|w| (w[0].1.hypot(w[0].2), w[1].1.hypot(w[1].2)))
+        .filter(|(before, after)| *before == 0.0 && *after > 0.0)
+        .map(|(_, after)| after)
+        .reduce(f64::min)
+}
+
+/// 需要转动摇杆覆盖外圈后才能判断
+fn range_loss(stick: &[(u64, f64, f64)], gate: &GateAnalysis) -> Option<f64> {
+    gate.shape?;
+    let reach = stick.iter().fold([0.0f64; 4], |reach, (_, x, y)| {
+        [
+            reach[0].max(*x),
+            reach[1].max(-x),
+            reach[2].max(*y),
+            reach[3].max(-y),
+        ]
+    });
+    Some((1.0 - reach.iter().copied().fold(f64::MAX, f64::min)).max(0.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::gamepad_util::ConnectionStatus;
+
+    #[test]
+    fn threshold_status() {
+        let threshold = Threshold::new(0.1, 0.2);
+        assert_eq!(threshold.status(None), MetricStatus::Unknown);
+        assert_eq!(threshold.status(Some(0.0)), MetricStatus::Pass);
+        assert_eq!(threshold.status(Some(0.099)), MetricStatus::Pass);
+        // 达到阈值即降级
+        assert_eq!(threshold.status(Some(0.1)), MetricStatus::Warn);
+        assert_eq!(threshold.status(Some(0.199)), MetricStatus::Warn);
+        assert_eq!(threshold.status(Some(0.2)), MetricStatus::Fail);
+    }
+
+    #[test]
+    fn grade_boundaries() {
+        assert_eq!(Grade::from_score(1.0, true), Grade::A);
+        assert_eq!(Grade::from_score(0.95, true), Grade::A);
+        assert_eq!(Grade::from_score(0.949, true), Grade::B);
+        assert_eq!(Grade::from_score(0.85, true), Grade::B);
+        assert_eq!(Grade::from_score(0.849, true), Grade::C);
+        // 有不合格指标时最高为 C
+        assert_eq!(Grade::from_score(0.95, false), Grade::C);
+        assert_eq!(Grade::from_score(0.7, false), Grade::C);
+        assert_eq!(Grade::from_score(0.699, true), Grade::D);
+        assert_eq!(Grade::from_score(0.5, true), Grade::D);
+        assert_eq!(Grade::from_score(0.499, true), Grade::F);
+    }
+
+    #[test]
+    fn invalid_thresholds_rejected() {
+        assert!(HealthThresholds::default().validate().is_ok());
+        let thresholds = HealthThresholds {
+            drift: Threshold::new(0.1, 0.05),
+            ..HealthThresholds::default()
+        };
+        assert!(thresholds.validate().is_err());
+    }
+
+    #[test]
+    fn inner_deadzone_from_departures() {
+        let stick = |radii: &[f64]| -> Vec<(u64, f64, f64)> {
+            radii
+                .iter()
+                .enumerate()
+                .map(|(i, r)| (i as u64 * 1000, *r, 0.0))
+                .collect()
+        };
+        // 取每次离开 0 时第一个样本中最小的
+        let radii = [0.0, 0.0, 0.3, 0.8, 0.0, 0.12, 0.5, 0.04, 0.0];
+        assert_eq!(inner_deadzone(&stick(&radii)), Some(0.12));
+        // 静止噪声不是死区：从未上报 0 时未知
+        let radii = [0.01, 0.02, 0.01, 0.5, 0.01];
+        assert_eq!(inner_deadzone(&stick(&radii)), None);
+    }
+
+    #[test]
+    fn evaluate_synthetic_data() {
+        // 1 ms 一个样本共 2 s：左摇杆离开 0 时最小为 0.12，右摇杆始终为 0
+        let samples: Vec<(u64, [f64; 4])> = (0..2000u64)
+            .map(|i| {
+                let lx = match i {
+                    1000 => 0.12,
+                    1500 => 0.3,
+                    _ => 0.0,
+                };
+                (i * 1000, [lx, 0.0, 0.0, 0.0])
+            })
+            .collect();
+        let data = HealthData {
+            samples,
+            polling_rate: PollingRateResult::new(),
+            gates: StickGates::default(),
+            chatter: vec![ButtonChatter {
+                button: "A".to_string(),
+                presses: 100,
+                chatter: 2,
+                min_interval: Some(5.0),
+            }],
+        };
+        let gamepad = GamepadInfo::unavailable(0, ConnectionStatus::Connected);
+        let report = HealthReport::evaluate(&gamepad, data, &HealthThresholds::default());
+
+        let status = |metric: &str, target: Option<&str>| {
+            report
+                .metrics
+                .iter()
+                .find(|m| m.metric == metric && m.target.as_deref() == target)
+                .map(|m| m.status)
+                .unwrap()
+        };
+        assert_eq!(status("polling_jitter", None), MetricStatus::Pass);
+        assert_eq!(status("inner_deadzone", Some("left")), MetricStatus::Warn);
+        assert_eq!(
+            status("inner_deadzone", Some("right")),
+            MetricStatus::Unknown
+        );
+        assert_eq!(status("drift", Some("left")), MetricStatus::Pass);
+        assert_eq!(status("noise", Some("right")), MetricStatus::Pass);
+        // 没有转动摇杆时无法判断外圈
+        assert_eq!(
+            status("circularity_error", Some("left")),
+            MetricStatus::Unknown
+        );
+        assert_eq!(status("range_loss", Some("right")), MetricStatus::Unknown);
+        assert_eq!(status("button_chatter", None), MetricStatus::Warn);
+
+        // 7 项已知：5 项通过、2 项警告
+        assert!((report.score - 6.0 / 7.0).abs() < 1e-9);
+        assert_eq!(report.grade, Some(Grade::B));
+        assert!(report.passed);
+    }
+}
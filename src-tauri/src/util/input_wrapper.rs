@@ -1,24 +1,43 @@
-use std::{collections::HashMap, sync::Mutex};
+use std::collections::HashMap;
+#[cfg(not(target_os = "linux"))]
+use std::collections::HashSet;
 use std::fmt;
+#[cfg(not(target_os = "linux"))]
+use std::sync::Mutex;
+use std::time::Duration;
+#[cfg(target_os = "windows")]
+use windows::Win32::UI::Input::XboxController::XINPUT_CAPABILITIES;
+#[cfg(not(target_os = "linux"))]
 use windows::Win32::UI::Input::XboxController::{
-    XInputGetBatteryInformation, XInputGetState, BATTERY_DEVTYPE, BATTERY_LEVEL_EMPTY,
-    BATTERY_LEVEL_FULL, BATTERY_LEVEL_LOW, BATTERY_LEVEL_MEDIUM, XINPUT_BATTERY_INFORMATION,
+    XInputGetBatteryInformation, XInputGetState, XInputSetState, BATTERY_DEVTYPE,
+    BATTERY_LEVEL_EMPTY, BATTERY_LEVEL_FULL, BATTERY_LEVEL_LOW, BATTERY_LEVEL_MEDIUM,
+    BATTERY_TYPE_ALKALINE, BATTERY_TYPE_NIMH, BATTERY_TYPE_WIRED, XINPUT_BATTERY_INFORMATION,
     XINPUT_GAMEPAD_A, XINPUT_GAMEPAD_B, XINPUT_GAMEPAD_BACK, XINPUT_GAMEPAD_BUTTON_FLAGS,
     XINPUT_GAMEPAD_DPAD_DOWN, XINPUT_GAMEPAD_DPAD_LEFT, XINPUT_GAMEPAD_DPAD_RIGHT,
     XINPUT_GAMEPAD_DPAD_UP, XINPUT_GAMEPAD_LEFT_SHOULDER, XINPUT_GAMEPAD_LEFT_THUMB,
     XINPUT_GAMEPAD_RIGHT_SHOULDER, XINPUT_GAMEPAD_RIGHT_THUMB, XINPUT_GAMEPAD_START,
-    XINPUT_GAMEPAD_X, XINPUT_GAMEPAD_Y, XINPUT_STATE, XUSER_MAX_COUNT,
+    XINPUT_GAMEPAD_X, XINPUT_GAMEPAD_Y, XINPUT_STATE, XINPUT_VIBRATION, XUSER_MAX_COUNT,
 };
 
+use crate::util::capabilities::DeviceCapabilities;
+#[cfg(not(target_os = "linux"))]
+use crate::util::capabilities::{AxisCapability, TriggerType};
+#[cfg(not(target_os = "linux"))]
+use crate::util::error::GamepadError;
+use crate::util::led::LedInfo;
+use crate::util::normalize::NativeRange;
+use crate::util::power::PowerInfo;
+#[cfg(not(target_os = "linux"))]
+use crate::util::power::{BatteryLevel, PowerSource};
+#[cfg(not(target_os = "linux"))]
+use crate::util::sdl_guid::{create_guid, BUS_USB, DRIVER_XINPUT};
+#[cfg(all(debug_assertions, not(target_os = "linux")))]
+use crate::util::sdl_guid::{BUS_VIRTUAL, DRIVER_VIRTUAL};
+
 // rand is only used for debug virtual device
-#[cfg(debug_assertions)]
+#[cfg(all(debug_assertions, not(target_os = "linux")))]
 use rand::Rng;
 
-enum InputType {
-    XInput,
-    GameInput,
-}
-
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
 pub enum Buttons {
     A,
@@ -37,6 +56,21 @@ pub enum Buttons {
     DPadRight,
     LeftTrigger,
     RightTrigger,
+    /// Xbox / PS / Home 键
+    Guide,
+    /// Share / Capture / Create 键
+    Share,
+    /// 触摸板按下
+    Touchpad,
+    /// 其他功能键（如麦克风键），来自原生的 KEY_MICMUTE；SDL2 映射的 misc1 已对应 Share
+    Misc,
+    /// 背键，最多四个
+    Paddle1,
+    Paddle2,
+    Paddle3,
+    Paddle4,
+    /// 未映射的原始按键，值为 evdev 键码
+    Other(u16),
 }
 
 impl fmt::Display for Buttons {
@@ -58,11 +92,25 @@ impl fmt::Display for Buttons {
             Buttons::DPadRight => write!(f, "DPadRight"),
             Buttons::LeftTrigger => write!(f, "LeftTrigger"),
             Buttons::RightTrigger => write!(f, "RightTrigger"),
+            Buttons::Guide => write!(f, "Guide"),
+            Buttons::Share => write!(f, "Share"),
+            Buttons::Touchpad => write!(f, "Touchpad"),
+            Buttons::Misc => write!(f, "Misc"),
+            Buttons::Paddle1 => write!(f, "Paddle1"),
+            Buttons::Paddle2 => write!(f, "Paddle2"),
+            Buttons::Paddle3 => write!(f, "Paddle3"),
+            Buttons::Paddle4 => write!(f, "Paddle4"),
+            Buttons::Other(code) => write!(f, "Other{:#x}", code),
         }
     }
 }
 
-const BUTTONS_MAP: [(Buttons, XINPUT_GAMEPAD_BUTTON_FLAGS); 14] = [
+// 仅 XInputGetStateEx 会上报 Guide 键
+#[cfg(not(target_os = "linux"))]
+const XINPUT_GAMEPAD_GUIDE: XINPUT_GAMEPAD_BUTTON_FLAGS = XINPUT_GAMEPAD_BUTTON_FLAGS(0x0400);
+
+#[cfg(not(target_os = "linux"))]
+const BUTTONS_MAP: [(Buttons, XINPUT_GAMEPAD_BUTTON_FLAGS); 15] = [
     (Buttons::A, XINPUT_GAMEPAD_A),
     (Buttons::B, XINPUT_GAMEPAD_B),
     (Buttons::X, XINPUT_GAMEPAD_X),
@@ -77,6 +125,7 @@ const BUTTONS_MAP: [(Buttons, XINPUT_GAMEPAD_BUTTON_FLAGS); 14] = [
     (Buttons::DPadDown, XINPUT_GAMEPAD_DPAD_DOWN),
     (Buttons::DPadLeft, XINPUT_GAMEPAD_DPAD_LEFT),
     (Buttons::DPadRight, XINPUT_GAMEPAD_DPAD_RIGHT),
+    (Buttons::Guide, XINPUT_GAMEPAD_GUIDE),
 ];
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
@@ -108,10 +157,50 @@ pub trait RawInput<T, E> {
     fn get_axis_val(&self) -> Option<(i16, i16, i16, i16)>;
 }
 
+/// 设备实际具备的轴、按键与附加功能
+pub trait Capabilities<E> {
+    fn get_capabilities(&self, id: u32) -> Result<DeviceCapabilities, E>;
+}
+
+/// 可选能力：震动马达
+pub trait Rumble<E> {
+    /// 以给定强度 (0..=65535) 驱动低频（大）/高频（小）马达，持续 duration；强度均为 0 时停止
+    fn set_rumble(&self, id: u32, low: u16, high: u16, duration: Duration) -> Result<(), E>;
+}
+
+/// 可选能力：玩家指示灯与 RGB 灯条
+pub trait Leds<E> {
+    fn get_leds(&self, id: u32) -> Result<LedInfo, E>;
+    /// 设置玩家编号（从 1 开始），0 熄灭玩家灯
+    fn set_player_led(&self, id: u32, player: u8) -> Result<(), E>;
+    fn set_lightbar(&self, id: u32, red: u8, green: u8, blue: u8) -> Result<(), E>;
+}
+
 #[derive(Debug, Clone)]
 pub struct Axis {
     pub axis: Axes,
+    /// 换算到 i16 全范围、向上为正的值，用于回报率记录
     pub value: i16,
+    /// 设备上报的原生值
+    pub raw: i32,
+    pub range: NativeRange,
+}
+
+impl Axis {
+    /// 原生值即为 i16 的设备
+    pub fn from_i16(axis: Axes, value: i16) -> Self {
+        Axis {
+            axis,
+            value,
+            raw: value as i32,
+            range: NativeRange::I16,
+        }
+    }
+
+    /// 按原生范围归一化到 [-1, 1]
+    pub fn normalized(&self) -> f64 {
+        self.range.normalize(self.raw)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -127,24 +216,132 @@ pub struct Gamepad {
     pub name: String,
     pub vendor_id: Option<u16>,
     pub product_id: Option<u16>,
+    pub version: Option<u16>,
+    /// Linux input 总线类型 (BUS_USB = 0x03, BUS_BLUETOOTH = 0x05)
+    pub bus_type: Option<u16>,
+    /// 序列号/唯一标识（如蓝牙地址），用于区分同型号设备
+    pub serial: Option<String>,
     pub guid: String,
-    pub power_info: String,
+    pub power: PowerInfo,
     pub axes: HashMap<Axes, Axis>,
     pub buttons: HashMap<Buttons, Button>,
 }
 
+#[cfg(not(target_os = "linux"))]
 #[derive(Debug)]
 pub struct XInput {
     state: Mutex<(XINPUT_STATE, XINPUT_BATTERY_INFORMATION)>,
+    /// 已连接槽位的 (vendor_id, product_id, version)
+    identities: Mutex<HashMap<u32, (u16, u16, u16)>>,
+}
+
+// XInputGetCapabilitiesEx 未公开，仅能按序号 108 从 xinput1_4.dll 导入
+#[cfg(target_os = "windows")]
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct XInputCapabilitiesEx {
+    capabilities: XINPUT_CAPABILITIES,
+    vendor_id: u16,
+    product_id: u16,
+    product_version: u16,
+    unknown1: u16,
+    unknown2: u32,
 }
 
-impl RawInput<(XINPUT_STATE, XINPUT_BATTERY_INFORMATION), String> for XInput {
+#[cfg(target_os = "windows")]
+#[link(name = "xinput1_4", kind = "raw-dylib")]
+extern "system" {
+    #[link_ordinal(108)]
+    fn XInputGetCapabilitiesEx(
+        unknown: u32,
+        user_index: u32,
+        flags: u32,
+        capabilities: *mut XInputCapabilitiesEx,
+    ) -> u32;
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "xinput1_4", kind = "raw-dylib")]
+extern "system" {
+    // 与 XInputGetState 相同，但 wButtons 额外包含 Guide 键
+    #[link_ordinal(100)]
+    fn XInputGetStateEx(user_index: u32, state: *mut XINPUT_STATE) -> u32;
+}
+
+#[cfg(target_os = "windows")]
+fn get_xinput_state(id: u32, state: &mut XINPUT_STATE) -> u32 {
+    unsafe { XInputGetStateEx(id, state) }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn get_xinput_state(id: u32, state: &mut XINPUT_STATE) -> u32 {
+    unsafe { XInputGetState(id, state) }
+}
+
+#[cfg(target_os = "windows")]
+fn query_xinput_identity(id: u32) -> Option<(u16, u16, u16)> {
+    let mut caps = XInputCapabilitiesEx::default();
+    let result = unsafe { XInputGetCapabilitiesEx(1, id, 0, &mut caps) };
+    if result == 0 {
+        Some((caps.vendor_id, caps.product_id, caps.product_version))
+    } else {
+        None
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn query_xinput_identity(_id: u32) -> Option<(u16, u16, u16)> {
+    None
+}
+
+#[cfg(target_os = "windows")]
+fn query_xinput_vibration(id: u32) -> bool {
+    let mut caps = XInputCapabilitiesEx::default();
+    let result = unsafe { XInputGetCapabilitiesEx(1, id, 0, &mut caps) };
+    let vibration = caps.capabilities.Vibration;
+    result == 0 && (vibration.wLeftMotorSpeed != 0 || vibration.wRightMotorSpeed != 0)
+}
+
+// 无法查询时按标准 Xbox 手柄处理
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+fn query_xinput_vibration(_id: u32) -> bool {
+    true
+}
+
+#[cfg(not(target_os = "linux"))]
+impl XInput {
+    fn identity(&self, id: u32) -> Option<(u16, u16, u16)> {
+        let mut identities = self.identities.lock().ok()?;
+        if let Some(identity) = identities.get(&id) {
+            return Some(*identity);
+        }
+        let identity = query_xinput_identity(id)?;
+        identities.insert(id, identity);
+        Some(identity)
+    }
+
+    /// 当前状态中按下的按键（不含扳机）；只读取真实设备，调试构建的虚拟手柄没有按键
+    pub fn pressed_buttons(&self) -> HashSet<Buttons> {
+        let Ok(state) = self.state.lock() else {
+            return HashSet::new();
+        };
+        BUTTONS_MAP
+            .iter()
+            .filter(|(_, flag)| state.0.Gamepad.wButtons.contains(*flag))
+            .map(|(btn, _)| btn.clone())
+            .collect()
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+impl RawInput<(XINPUT_STATE, XINPUT_BATTERY_INFORMATION), GamepadError> for XInput {
     fn new() -> Self {
         XInput {
             state: Mutex::new((
                 XINPUT_STATE::default(),
                 XINPUT_BATTERY_INFORMATION::default(),
             )),
+            identities: Mutex::new(HashMap::new()),
         }
     }
 
@@ -154,33 +351,39 @@ impl RawInput<(XINPUT_STATE, XINPUT_BATTERY_INFORMATION), String> for XInput {
         }
     }
 
-    fn get_state(&self) -> Result<(XINPUT_STATE, XINPUT_BATTERY_INFORMATION), String> {
+    fn get_state(&self) -> Result<(XINPUT_STATE, XINPUT_BATTERY_INFORMATION), GamepadError> {
         if let Ok(current_state) = self.state.lock() {
             return Ok(*current_state);
         }
-        Err("Failed to lock state".to_string())
+        Err(GamepadError::lock("state"))
     }
 
-    fn update(&self, id: u32) -> Result<(XINPUT_STATE, XINPUT_BATTERY_INFORMATION), String> {
-        if let Ok(mut current_state) = self.state.lock() {
-            let mut state = *current_state;
-            let result = unsafe { XInputGetState(id, &mut state.0) };
-            unsafe {
-                XInputGetBatteryInformation(id, BATTERY_DEVTYPE(0u8), &mut state.1);
-            }
-            if result == 0 {
-                // real device
-                *current_state = state;
-            } else {
-                // no device => clear state
-                *current_state = (
-                    XINPUT_STATE::default(),
-                    XINPUT_BATTERY_INFORMATION::default(),
-                );
-            }
+    fn update(&self, id: u32) -> Result<(XINPUT_STATE, XINPUT_BATTERY_INFORMATION), GamepadError> {
+        let mut state = self.get_state()?;
+        let result = get_xinput_state(id, &mut state.0);
+        unsafe {
+            XInputGetBatteryInformation(id, BATTERY_DEVTYPE(0u8), &mut state.1);
+        }
+        if result == 0 {
+            // real device
+            self.set_state(state);
             return Ok(state);
         }
-        Err(format!("Failed to get state for device ID {}", id))
+        // no device => clear state
+        if let Ok(mut identities) = self.identities.lock() {
+            identities.remove(&id);
+        }
+        let state = (
+            XINPUT_STATE::default(),
+            XINPUT_BATTERY_INFORMATION::default(),
+        );
+        self.set_state(state);
+        // 调试构建下槽位 0 保留虚拟手柄
+        #[cfg(debug_assertions)]
+        if id == 0 {
+            return Ok(state);
+        }
+        Err(GamepadError::not_connected(id))
     }
 
     fn all_device_id(&self) -> Vec<u32> {
@@ -201,60 +404,63 @@ impl RawInput<(XINPUT_STATE, XINPUT_BATTERY_INFORMATION), String> for XInput {
         device_ids
     }
 
-    fn get_controller(&self, id: u32) -> Result<Gamepad, String> {
+    fn get_controller(&self, id: u32) -> Result<Gamepad, GamepadError> {
         // 获取当前状态
         let (xi_state, batt) = if let Ok(state) = self.state.lock() {
             *state
         } else {
-            return Err("Failed to lock state".to_string());
+            return Err(GamepadError::lock("state"));
         };
 
         // real device mapping
         let battery_state = &batt;
+        let identity = self.identity(id);
+        let name = format!("Xinput Controller {}", id);
+        let (vendor, product, version) = identity.unwrap_or_default();
         let mut gamepad = Gamepad {
             id,
-            name: "XInput Controller".to_string(),
-            vendor_id: None,
-            product_id: None,
-            guid: String::new(),
-            power_info: String::new(),
+            // XInput 设备按 USB 处理
+            guid: create_guid(BUS_USB, vendor, product, version, &name, DRIVER_XINPUT),
+            name,
+            vendor_id: identity.map(|i| i.0),
+            product_id: identity.map(|i| i.1),
+            version: identity.map(|i| i.2),
+            bus_type: identity.map(|_| BUS_USB),
+            serial: None,
+            power: PowerInfo::unknown(),
             axes: HashMap::new(),
             buttons: HashMap::new(),
         };
-        gamepad.power_info = match battery_state.BatteryLevel {
-            BATTERY_LEVEL_EMPTY => "Empty".to_string(),
-            BATTERY_LEVEL_LOW => "Low".to_string(),
-            BATTERY_LEVEL_MEDIUM => "Medium".to_string(),
-            BATTERY_LEVEL_FULL => "Full".to_string(),
-            _ => "Unknown".to_string(),
+        // XInput 只提供电池类型与四档电量，没有百分比和充电状态
+        gamepad.power = match battery_state.BatteryType {
+            BATTERY_TYPE_WIRED => PowerInfo::new(PowerSource::Wired),
+            BATTERY_TYPE_ALKALINE | BATTERY_TYPE_NIMH => PowerInfo {
+                level: match battery_state.BatteryLevel {
+                    BATTERY_LEVEL_EMPTY => BatteryLevel::Empty,
+                    BATTERY_LEVEL_LOW => BatteryLevel::Low,
+                    BATTERY_LEVEL_MEDIUM => BatteryLevel::Medium,
+                    BATTERY_LEVEL_FULL => BatteryLevel::Full,
+                    _ => BatteryLevel::Unknown,
+                },
+                ..PowerInfo::new(PowerSource::Wireless)
+            },
+            _ => PowerInfo::unknown(),
         };
         gamepad.axes.insert(
             Axes::LeftThumbX,
-            Axis {
-                axis: Axes::LeftThumbX,
-                value: xi_state.Gamepad.sThumbLX,
-            },
+            Axis::from_i16(Axes::LeftThumbX, xi_state.Gamepad.sThumbLX),
         );
         gamepad.axes.insert(
             Axes::LeftThumbY,
-            Axis {
-                axis: Axes::LeftThumbY,
-                value: xi_state.Gamepad.sThumbLY,
-            },
+            Axis::from_i16(Axes::LeftThumbY, xi_state.Gamepad.sThumbLY),
         );
         gamepad.axes.insert(
             Axes::RightThumbX,
-            Axis {
-                axis: Axes::RightThumbX,
-                value: xi_state.Gamepad.sThumbRX,
-            },
+            Axis::from_i16(Axes::RightThumbX, xi_state.Gamepad.sThumbRX),
         );
         gamepad.axes.insert(
             Axes::RightThumbY,
-            Axis {
-                axis: Axes::RightThumbY,
-                value: xi_state.Gamepad.sThumbRY,
-            },
+            Axis::from_i16(Axes::RightThumbY, xi_state.Gamepad.sThumbRY),
         );
         BUTTONS_MAP.iter().for_each(|(btn, flag)| {
             let pressed = xi_state.Gamepad.wButtons.contains(*flag);
@@ -300,45 +506,35 @@ impl RawInput<(XINPUT_STATE, XINPUT_BATTERY_INFORMATION), String> for XInput {
                 .as_secs_f64();
             let f = t * 2.0 * PI / 5.0;
             let axis_val = |v: f64| (v * i16::MAX as f64) as i16;
+            let name = format!("Dummy {}", id);
             let mut gamepad = Gamepad {
                 id,
-                name: format!("Dummy {}", id),
+                guid: create_guid(BUS_VIRTUAL, 0, 0, 0, &name, DRIVER_VIRTUAL),
+                name,
                 vendor_id: None,
                 product_id: None,
-                guid: String::new(),
-                power_info: "Virtual".to_string(),
+                version: None,
+                bus_type: None,
+                serial: None,
+                power: PowerInfo::unknown(),
                 axes: HashMap::new(),
                 buttons: HashMap::new(),
             };
             // axes
             gamepad.axes.insert(
                 Axes::LeftThumbX,
-                Axis {
-                    axis: Axes::LeftThumbX,
-                    value: axis_val(f.cos()),
-                },
+                Axis::from_i16(Axes::LeftThumbX, axis_val(f.cos())),
             );
             gamepad.axes.insert(
                 Axes::LeftThumbY,
-                Axis {
-                    axis: Axes::LeftThumbY,
-                    value: axis_val(f.sin()),
-                },
-            );
-            gamepad.axes.insert(
-                Axes::RightThumbX,
-                Axis {
-                    axis: Axes::RightThumbX,
-                    value: 0,
-                },
-            );
-            gamepad.axes.insert(
-                Axes::RightThumbY,
-                Axis {
-                    axis: Axes::RightThumbY,
-                    value: 0,
-                },
+                Axis::from_i16(Axes::LeftThumbY, axis_val(f.sin())),
             );
+            gamepad
+                .axes
+                .insert(Axes::RightThumbX, Axis::from_i16(Axes::RightThumbX, 0));
+            gamepad
+                .axes
+                .insert(Axes::RightThumbY, Axis::from_i16(Axes::RightThumbY, 0));
             // buttons random
             let mut rng = rng();
             for (btn, _) in BUTTONS_MAP.iter() {
@@ -406,3 +602,99 @@ impl RawInput<(XINPUT_STATE, XINPUT_BATTERY_INFORMATION), String> for XInput {
         ))
     }
 }
+
+// XInput 没有时长参数，由调用方在结束时停止
+#[cfg(not(target_os = "linux"))]
+impl Rumble<GamepadError> for XInput {
+    fn set_rumble(
+        &self,
+        id: u32,
+        low: u16,
+        high: u16,
+        _duration: Duration,
+    ) -> Result<(), GamepadError> {
+        let vibration = XINPUT_VIBRATION {
+            wLeftMotorSpeed: low,
+            wRightMotorSpeed: high,
+        };
+        match unsafe { XInputSetState(id, &vibration) } {
+            0 => Ok(()),
+            _ => Err(GamepadError::not_connected(id)),
+        }
+    }
+}
+
+// XInput 的玩家灯（Xbox 按键灯环）由系统按槽位点亮，没有设置接口
+#[cfg(not(target_os = "linux"))]
+impl Leds<GamepadError> for XInput {
+    fn get_leds(&self, id: u32) -> Result<LedInfo, GamepadError> {
+        let mut state = XINPUT_STATE::default();
+        if get_xinput_state(id, &mut state) != 0 {
+            return Err(GamepadError::not_connected(id));
+        }
+        Ok(LedInfo {
+            player_count: XUSER_MAX_COUNT as u8,
+            player: Some(id as u8 + 1),
+            player_settable: false,
+            lightbar: false,
+        })
+    }
+
+    fn set_player_led(&self, id: u32, player: u8) -> Result<(), GamepadError> {
+        if self.get_leds(id)?.player == Some(player) {
+            return Ok(());
+        }
+        Err(GamepadError::BackendUnavailable(format!(
+            "XInput player LED follows the controller slot ({})",
+            id + 1
+        )))
+    }
+
+    fn set_lightbar(&self, id: u32, _red: u8, _green: u8, _blue: u8) -> Result<(), GamepadError> {
+        Err(GamepadError::BackendUnavailable(format!(
+            "Device ID {} has no lightbar",
+            id
+        )))
+    }
+}
+
+// XInput 的布局固定：两根 16 位摇杆、两个 8 位模拟扳机
+#[cfg(not(target_os = "linux"))]
+impl Capabilities<GamepadError> for XInput {
+    fn get_capabilities(&self, id: u32) -> Result<DeviceCapabilities, GamepadError> {
+        let leds = self.get_leds(id)?;
+        let mut battery = XINPUT_BATTERY_INFORMATION::default();
+        unsafe {
+            XInputGetBatteryInformation(id, BATTERY_DEVTYPE(0u8), &mut battery);
+        }
+
+        let axes = [
+            Axes::LeftThumbX,
+            Axes::LeftThumbY,
+            Axes::RightThumbX,
+            Axes::RightThumbY,
+        ]
+        .iter()
+        .map(|axis| AxisCapability::new(axis.to_string(), i16::MIN as i32, i16::MAX as i32))
+        .chain(
+            [Buttons::LeftTrigger, Buttons::RightTrigger]
+                .iter()
+                .map(|btn| AxisCapability::new(btn.to_string(), 0, u8::MAX as i32)),
+        )
+        .collect();
+        Ok(DeviceCapabilities {
+            axes,
+            buttons: BUTTONS_MAP.iter().map(|(btn, _)| btn.to_string()).collect(),
+            left_trigger: TriggerType::Analog,
+            right_trigger: TriggerType::Analog,
+            rumble: query_xinput_vibration(id),
+            motion: false,
+            touchpad: false,
+            battery: matches!(
+                battery.BatteryType,
+                BATTERY_TYPE_ALKALINE | BATTERY_TYPE_NIMH
+            ),
+            leds,
+        })
+    }
+}
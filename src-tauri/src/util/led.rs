@@ -0,0 +1,221 @@
+use serde::{Deserialize, Serialize};
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::io;
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+
+#[cfg(target_os = "linux")]
+const SYS_LEDS_DIR: &str = "/sys/class/leds";
+// xpad 的 LED 亮度 6..=9 表示常亮玩家 1..=4
+#[cfg(target_os = "linux")]
+const XPAD_PLAYER_BASE: u32 = 5;
+#[cfg(target_os = "linux")]
+const XPAD_PLAYER_COUNT: u8 = 4;
+
+/// 手柄的指示灯能力与当前状态
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LedInfo {
+    /// 玩家指示灯可表示的玩家数，0 表示没有
+    pub player_count: u8,
+    /// 当前玩家编号（从 1 开始），无法读取时为 None
+    pub player: Option<u8>,
+    /// XInput 的玩家灯由槽位决定，只能读取
+    pub player_settable: bool,
+    /// 是否有 RGB 灯条
+    pub lightbar: bool,
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone)]
+enum PlayerLeds {
+    /// 每个玩家一颗灯（hid-playstation、hid-nintendo 等的 *:player-N）
+    Indicators(Vec<PathBuf>),
+    /// xpad 用单个 LED 的亮度值表示灯环图案
+    Xpad(PathBuf),
+}
+
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone)]
+enum Lightbar {
+    /// multicolor LED，颜色写入 multi_intensity
+    Multicolor(PathBuf),
+    /// 红绿蓝分别是独立的 LED（hid-sony）
+    Channels([PathBuf; 3]),
+}
+
+/// 同一手柄在 /sys/class/leds 下的指示灯
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Default)]
+pub struct LedSet {
+    player: Option<PlayerLeds>,
+    lightbar: Option<Lightbar>,
+}
+
+#[cfg(target_os = "linux")]
+impl LedSet {
+    /// 查找 device 链接指向 device_dir（手柄的物理设备目录）的 LED
+    pub fn find(device_dir: &Path) -> Self {
+        let mut leds: Vec<(String, PathBuf)> = fs::read_dir(SYS_LEDS_DIR)
+            .into_iter()
+            .flatten()
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                fs::canonicalize(path.join("device")).ok().as_deref() == Some(device_dir)
+            })
+            .filter_map(|path| Some((path.file_name()?.to_str()?.to_string(), path)))
+            .collect();
+        leds.sort();
+
+        let mut indicators: Vec<(u8, PathBuf)> = leds
+            .iter()
+            .filter_map(|(name, path)| Some((player_number(name)?, path.clone())))
+            .collect();
+        indicators.sort();
+        let player = if !indicators.is_empty() {
+            Some(PlayerLeds::Indicators(
+                indicators.into_iter().map(|(_, path)| path).collect(),
+            ))
+        } else {
+            leds.iter()
+                .find(|(name, _)| name.starts_with("xpad"))
+                .map(|(_, path)| PlayerLeds::Xpad(path.clone()))
+        };
+
+        let channel = |color: &str| {
+            leds.iter()
+                .find(|(name, _)| name.ends_with(&format!(":{}", color)))
+                .map(|(_, path)| path.clone())
+        };
+        let multicolor = leds
+            .iter()
+            .find(|(name, path)| name.contains(":rgb:") && path.join("multi_intensity").exists());
+        let lightbar = match (
+            multicolor,
+            channel("red"),
+            channel("green"),
+            channel("blue"),
+        ) {
+            (Some((_, path)), ..) => Some(Lightbar::Multicolor(path.clone())),
+            (None, Some(red), Some(green), Some(blue)) => {
+                Some(Lightbar::Channels([red, green, blue]))
+            }
+            _ => None,
+        };
+
+        LedSet { player, lightbar }
+    }
+
+    pub fn info(&self) -> LedInfo {
+        let (player_count, player) = match &self.player {
+            Some(PlayerLeds::Indicators(leds)) => (
+                leds.len() as u8,
+                // 多颗灯亮时（如 DualSense 的图案）取最后一颗
+                leds.iter()
+                    .rposition(|path| read_u32(path, "brightness").unwrap_or(0) > 0)
+                    .map(|index| index as u8 + 1),
+            ),
+            Some(PlayerLeds::Xpad(path)) => (
+                XPAD_PLAYER_COUNT,
+                read_u32(path, "brightness")
+                    .filter(|value| {
+                        (1..=XPAD_PLAYER_COUNT as u32)
+                            .contains(&value.wrapping_sub(XPAD_PLAYER_BASE))
+                    })
+                    .map(|value| (value - XPAD_PLAYER_BASE) as u8),
+            ),
+            None => (0, None),
+        };
+        LedInfo {
+            player_count,
+            player,
+            player_settable: player_count > 0,
+            lightbar: self.lightbar.is_some(),
+        }
+    }
+
+    /// 点亮第 player 颗玩家灯（从 1 开始），0 熄灭全部
+    pub fn set_player(&self, player: u8) -> io::Result<bool> {
+        match &self.player {
+            Some(PlayerLeds::Indicators(leds)) => {
+                for (index, path) in leds.iter().enumerate() {
+                    let on = index + 1 == player as usize;
+                    let value = if on {
+                        read_u32(path, "max_brightness").unwrap_or(1)
+                    } else {
+                        0
+                    };
+                    write_u32(path, "brightness", value)?;
+                }
+                Ok(true)
+            }
+            Some(PlayerLeds::Xpad(path)) => {
+                let value = if player == 0 {
+                    0
+                } else {
+                    XPAD_PLAYER_BASE + player as u32
+                };
+                write_u32(path, "brightness", value)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub fn set_lightbar(&self, red: u8, green: u8, blue: u8) -> io::Result<bool> {
+        match &self.lightbar {
+            Some(Lightbar::Multicolor(path)) => {
+                // multi_index 给出 multi_intensity 各分量的颜色顺序
+                let order = fs::read_to_string(path.join("multi_index"))?;
+                let intensity: Vec<String> = order
+                    .split_whitespace()
+                    .map(|color| match color {
+                        "red" => red,
+                        "green" => green,
+                        "blue" => blue,
+                        _ => 0,
+                    })
+                    .map(|value| value.to_string())
+                    .collect();
+                fs::write(path.join("multi_intensity"), intensity.join(" "))?;
+                let max = read_u32(path, "max_brightness").unwrap_or(255);
+                write_u32(path, "brightness", max)?;
+                Ok(true)
+            }
+            Some(Lightbar::Channels(channels)) => {
+                for (path, value) in channels.iter().zip([red, green, blue]) {
+                    let max = read_u32(path, "max_brightness").unwrap_or(255);
+                    write_u32(path, "brightness", value as u32 * max / 255)?;
+                }
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// 解析 "<设备>:<颜色>:player-N" 形式的 LED 名称
+#[cfg(target_os = "linux")]
+fn player_number(name: &str) -> Option<u8> {
+    name.rsplit(':')
+        .next()?
+        .strip_prefix("player-")?
+        .parse::<u8>()
+        .ok()
+}
+
+#[cfg(target_os = "linux")]
+fn read_u32(path: &Path, name: &str) -> Option<u32> {
+    fs::read_to_string(path.join(name))
+        .ok()?
+        .trim()
+        .parse::<u32>()
+        .ok()
+}
+
+#[cfg(target_os = "linux")]
+fn write_u32(path: &Path, name: &str, value: u32) -> io::Result<()> {
+    fs::write(path.join(name), value.to_string())
+}
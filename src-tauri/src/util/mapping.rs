@@ -0,0 +1,669 @@
+use crate::util::error::GamepadError;
+use crate::util::input_wrapper::{Axes, Buttons, Gamepad};
+use crate::util::sdl_guid::strip_crc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// 用户自定义映射，位于应用配置目录，优先于内置映射
+pub const MAPPINGS_FILE: &str = "gamecontrollerdb.txt";
+const BUNDLED_DB: &str = include_str!("../../resources/gamecontrollerdb.txt");
+
+#[cfg(target_os = "linux")]
+const PLATFORM: &str = "Linux";
+#[cfg(target_os = "windows")]
+const PLATFORM: &str = "Windows";
+#[cfg(target_os = "macos")]
+const PLATFORM: &str = "Mac OS X";
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+const PLATFORM: &str = "";
+
+// SDL 的 misc1 即 Xbox Share / Switch Capture 键
+const BUTTON_NAMES: [(&str, Buttons); 23] = [
+    ("a", Buttons::A),
+    ("b", Buttons::B),
+    ("x", Buttons::X),
+    ("y", Buttons::Y),
+    ("back", Buttons::Back),
+    ("start", Buttons::Start),
+    ("leftshoulder", Buttons::LeftShoulder),
+    ("rightshoulder", Buttons::RightShoulder),
+    ("leftstick", Buttons::LeftThumb),
+    ("rightstick", Buttons::RightThumb),
+    ("dpup", Buttons::DPadUp),
+    ("dpdown", Buttons::DPadDown),
+    ("dpleft", Buttons::DPadLeft),
+    ("dpright", Buttons::DPadRight),
+    ("lefttrigger", Buttons::LeftTrigger),
+    ("righttrigger", Buttons::RightTrigger),
+    ("guide", Buttons::Guide),
+    ("misc1", Buttons::Share),
+    ("touchpad", Buttons::Touchpad),
+    ("paddle1", Buttons::Paddle1),
+    ("paddle2", Buttons::Paddle2),
+    ("paddle3", Buttons::Paddle3),
+    ("paddle4", Buttons::Paddle4),
+];
+
+// 顺序与 thumbs 元组 (lx, ly, rx, ry) 一致
+const AXIS_NAMES: [(&str, Axes); 4] = [
+    ("leftx", Axes::LeftThumbX),
+    ("lefty", Axes::LeftThumbY),
+    ("rightx", Axes::RightThumbX),
+    ("righty", Axes::RightThumbY),
+];
+
+/// 轴的取值范围：完整轴或半轴（映射中的 "+a0" / "-a0"）
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AxisRange {
+    Full,
+    Positive,
+    Negative,
+}
+
+impl AxisRange {
+    fn bounds(self) -> (f64, f64) {
+        match self {
+            AxisRange::Full => (i16::MIN as f64, i16::MAX as f64),
+            AxisRange::Positive => (0.0, i16::MAX as f64),
+            AxisRange::Negative => (0.0, i16::MIN as f64),
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            AxisRange::Full => "",
+            AxisRange::Positive => "+",
+            AxisRange::Negative => "-",
+        }
+    }
+}
+
+/// 原始输入：按 SDL 的编号规则索引的按键、HAT 与轴
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawSource {
+    Button(usize),
+    /// HAT 索引与方向掩码（1 上、2 右、4 下、8 左）
+    Hat(usize, u8),
+    Axis {
+        index: usize,
+        range: AxisRange,
+        invert: bool,
+    },
+}
+
+impl RawSource {
+    /// 读取输入的激活程度 [0, 1]；索引不存在时返回 None
+    fn read(&self, raw: &RawJoystick) -> Option<f64> {
+        match *self {
+            RawSource::Button(index) => raw.buttons.get(index).map(|&b| b as u8 as f64),
+            RawSource::Hat(index, mask) => raw
+                .hats
+                .get(index)
+                .map(|&hat| (hat & mask != 0) as u8 as f64),
+            RawSource::Axis {
+                index,
+                range,
+                invert,
+            } => raw.axes.get(index).map(|&value| {
+                let (mut min, mut max) = range.bounds();
+                if invert {
+                    std::mem::swap(&mut min, &mut max);
+                }
+                ((value as f64 - min) / (max - min)).clamp(0.0, 1.0)
+            }),
+        }
+    }
+
+    fn parse(value: &str) -> Result<Self, GamepadError> {
+        let invalid = || GamepadError::invalid(format!("Invalid mapping source: {}", value));
+        let (range, rest) = match value.as_bytes().first() {
+            Some(b'+') => (AxisRange::Positive, &value[1..]),
+            Some(b'-') => (AxisRange::Negative, &value[1..]),
+            _ => (AxisRange::Full, value),
+        };
+        let (invert, rest) = match rest.strip_suffix('~') {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let is_axis = rest.starts_with('a');
+        if !is_axis && (range != AxisRange::Full || invert) {
+            return Err(invalid());
+        }
+
+        if let Some(index) = rest.strip_prefix('b') {
+            return index.parse().map(RawSource::Button).map_err(|_| invalid());
+        }
+        if let Some(hat) = rest.strip_prefix('h') {
+            let (index, mask) = hat.split_once('.').ok_or_else(invalid)?;
+            return Ok(RawSource::Hat(
+                index.parse().map_err(|_| invalid())?,
+                mask.parse().map_err(|_| invalid())?,
+            ));
+        }
+        if let Some(index) = rest.strip_prefix('a') {
+            return Ok(RawSource::Axis {
+                index: index.parse().map_err(|_| invalid())?,
+                range,
+                invert,
+            });
+        }
+        Err(invalid())
+    }
+
+    pub fn to_sdl_string(self) -> String {
+        match self {
+            RawSource::Button(index) => format!("b{}", index),
+            RawSource::Hat(index, mask) => format!("h{}.{}", index, mask),
+            RawSource::Axis {
+                index,
+                range,
+                invert,
+            } => format!(
+                "{}a{}{}",
+                range.prefix(),
+                index,
+                if invert { "~" } else { "" }
+            ),
+        }
+    }
+}
+
+/// 映射目标：现有的逻辑按键/轴，轴可以是半轴
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MappingTarget {
+    Button(Buttons),
+    Axis(Axes, AxisRange),
+}
+
+impl MappingTarget {
+    fn parse(key: &str) -> Option<Self> {
+        let (range, name) = match key.as_bytes().first() {
+            Some(b'+') => (AxisRange::Positive, &key[1..]),
+            Some(b'-') => (AxisRange::Negative, &key[1..]),
+            _ => (AxisRange::Full, key),
+        };
+        if let Some((_, axis)) = AXIS_NAMES.iter().find(|(n, _)| *n == name) {
+            return Some(MappingTarget::Axis(axis.clone(), range));
+        }
+        if range != AxisRange::Full {
+            return None;
+        }
+        BUTTON_NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, btn)| MappingTarget::Button(btn.clone()))
+    }
+
+    pub fn to_sdl_string(&self) -> String {
+        match self {
+            MappingTarget::Button(btn) => BUTTON_NAMES
+                .iter()
+                .find(|(_, b)| b == btn)
+                .map(|(n, _)| n.to_string())
+                .unwrap_or_default(),
+            MappingTarget::Axis(axis, range) => AXIS_NAMES
+                .iter()
+                .find(|(_, a)| a == axis)
+                .map(|(n, _)| format!("{}{}", range.prefix(), n))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    pub target: MappingTarget,
+    pub source: RawSource,
+}
+
+/// 设备的原始输入快照，轴已归一化到 i16 全范围（Y 轴向下为正，与 SDL 一致）
+#[derive(Debug, Clone, Default)]
+pub struct RawJoystick {
+    pub buttons: Vec<bool>,
+    pub axes: Vec<i16>,
+    pub hats: Vec<u8>,
+}
+
+/// 应用映射后的逻辑状态，Y 轴向上为正（与 XInput 一致）
+#[derive(Debug, Clone, Default)]
+pub struct MappedState {
+    pub thumbs: (i16, i16, i16, i16),
+    pub triggers: (u8, u8),
+    pub pressed: HashSet<Buttons>,
+}
+
+/// gamecontrollerdb.txt 中的一条映射
+#[derive(Debug, Clone)]
+pub struct ControllerMapping {
+    pub guid: String,
+    pub name: String,
+    pub platform: Option<String>,
+    pub bindings: Vec<Binding>,
+}
+
+impl ControllerMapping {
+    /// 创建当前平台的映射
+    pub fn new(guid: &str, name: &str, bindings: Vec<Binding>) -> Self {
+        ControllerMapping {
+            guid: guid.to_lowercase(),
+            name: name.replace(',', " "),
+            platform: Some(PLATFORM.to_string()).filter(|p| !p.is_empty()),
+            bindings,
+        }
+    }
+
+    /// 解析一行映射：GUID,名称,目标:输入,...,platform:平台,
+    /// 不支持的目标（如 SDL3 新增的 misc2..misc6）会被忽略
+    pub fn parse(line: &str) -> Result<Self, GamepadError> {
+        let mut fields = line.trim().split(',');
+        let guid = fields.next().unwrap_or_default().trim().to_lowercase();
+        let is_hex = guid.len() == 32 && guid.chars().all(|c| c.is_ascii_hexdigit());
+        if !is_hex && guid != "xinput" {
+            return Err(GamepadError::invalid(format!(
+                "Invalid mapping GUID: {}",
+                guid
+            )));
+        }
+        let name = fields
+            .next()
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| GamepadError::invalid("Mapping name is missing"))?
+            .to_string();
+
+        let mut platform = None;
+        let mut bindings = Vec::new();
+        for field in fields.map(str::trim).filter(|f| !f.is_empty()) {
+            let (key, value) = field.split_once(':').ok_or_else(|| {
+                GamepadError::invalid(format!("Invalid mapping field: {}", field))
+            })?;
+            if key == "platform" {
+                platform = Some(value.to_string());
+                continue;
+            }
+            if let Some(target) = MappingTarget::parse(key) {
+                bindings.push(Binding {
+                    target,
+                    source: RawSource::parse(value)?,
+                });
+            }
+        }
+
+        Ok(ControllerMapping {
+            guid,
+            name,
+            platform,
+            bindings,
+        })
+    }
+
+    pub fn to_sdl_string(&self) -> String {
+        let mut line = format!("{},{},", self.guid, self.name);
+        for binding in &self.bindings {
+            line.push_str(&format!(
+                "{}:{},",
+                binding.target.to_sdl_string(),
+                binding.source.to_sdl_string()
+            ));
+        }
+        if let Some(platform) = &self.platform {
+            line.push_str(&format!("platform:{},", platform));
+        }
+        line
+    }
+
+    fn matches_platform(&self) -> bool {
+        self.platform.as_deref().is_none_or(|p| p == PLATFORM)
+    }
+
+    /// 将原始输入映射为逻辑按键与轴
+    pub fn apply(&self, raw: &RawJoystick) -> MappedState {
+        let mut state = MappedState::default();
+        let mut thumbs = [0i32; 4];
+
+        for binding in &self.bindings {
+            let Some(t) = binding.source.read(raw) else {
+                continue;
+            };
+            match &binding.target {
+                MappingTarget::Button(Buttons::LeftTrigger) => {
+                    state.triggers.0 = state.triggers.0.max((t * 255.0).round() as u8);
+                }
+                MappingTarget::Button(Buttons::RightTrigger) => {
+                    state.triggers.1 = state.triggers.1.max((t * 255.0).round() as u8);
+                }
+                MappingTarget::Button(btn) => {
+                    if t > 0.5 {
+                        state.pressed.insert(btn.clone());
+                    }
+                }
+                MappingTarget::Axis(axis, range) => {
+                    let Some(index) = AXIS_NAMES.iter().position(|(_, a)| a == axis) else {
+                        continue;
+                    };
+                    let (min, max) = range.bounds();
+                    // 半轴只在激活时写入，避免覆盖另一半
+                    if *range == AxisRange::Full || t > 0.0 {
+                        thumbs[index] = (min + t * (max - min)).round() as i32;
+                    }
+                }
+            }
+        }
+
+        let clamp = |v: i32| v.clamp(i16::MIN as i32, i16::MAX as i32) as i16;
+        state.thumbs = (
+            clamp(thumbs[0]),
+            clamp(-thumbs[1]),
+            clamp(thumbs[2]),
+            clamp(-thumbs[3]),
+        );
+        state
+    }
+}
+
+/// 按 GUID 索引的映射集合，只保留当前平台可用的条目
+#[derive(Debug, Clone, Default)]
+pub struct MappingDb {
+    mappings: HashMap<String, ControllerMapping>,
+}
+
+impl MappingDb {
+    /// 解析 gamecontrollerdb.txt，跳过注释与无法解析的行
+    pub fn parse(text: &str) -> Self {
+        let mut db = MappingDb::default();
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| ControllerMapping::parse(line).ok())
+            .filter(|mapping| mapping.matches_platform())
+            .for_each(|mapping| db.insert(mapping));
+        db
+    }
+
+    /// 先按完整 GUID 查找，再忽略版本号查找（与 SDL 一致，均忽略名称 CRC）
+    pub fn get(&self, guid: &str) -> Option<&ControllerMapping> {
+        let key = strip_crc(&guid.to_lowercase());
+        self.mappings.get(&key).or_else(|| {
+            if key.len() != 32 {
+                return None;
+            }
+            self.mappings
+                .get(&format!("{}0000{}", &key[..24], &key[28..]))
+        })
+    }
+
+    pub fn insert(&mut self, mapping: ControllerMapping) {
+        self.mappings.insert(strip_crc(&mapping.guid), mapping);
+    }
+
+    pub fn remove(&mut self, guid: &str) -> Option<ControllerMapping> {
+        self.mappings.remove(&strip_crc(&guid.to_lowercase()))
+    }
+
+    pub fn to_lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .mappings
+            .values()
+            .map(ControllerMapping::to_sdl_string)
+            .collect();
+        lines.sort();
+        lines
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MappingSource {
+    /// 后端自带的标准布局（XInput 或内核手柄布局）
+    Native,
+    Bundled,
+    User,
+}
+
+/// 当前设备实际使用的映射
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AppliedMapping {
+    pub guid: String,
+    pub name: String,
+    pub source: MappingSource,
+    /// SDL 格式的映射字符串，使用标准布局时为空
+    pub mapping: Option<String>,
+}
+
+impl AppliedMapping {
+    pub fn native(gamepad: &Gamepad) -> Self {
+        AppliedMapping {
+            guid: gamepad.guid.clone(),
+            name: gamepad.name.clone(),
+            source: MappingSource::Native,
+            mapping: None,
+        }
+    }
+}
+
+/// 内置映射与用户映射
+#[derive(Debug)]
+pub struct MappingStore {
+    path: Option<PathBuf>,
+    bundled: MappingDb,
+    user: RwLock<MappingDb>,
+}
+
+impl MappingStore {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let user = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .map(|content| MappingDb::parse(&content))
+            .unwrap_or_default();
+        MappingStore {
+            path,
+            bundled: MappingDb::parse(BUNDLED_DB),
+            user: RwLock::new(user),
+        }
+    }
+
+    /// 查找设备的映射，用户映射优先
+    pub fn lookup(&self, guid: &str) -> Option<(ControllerMapping, MappingSource)> {
+        if let Some(mapping) = self
+            .user
+            .read()
+            .ok()
+            .and_then(|user| user.get(guid).cloned())
+        {
+            return Some((mapping, MappingSource::User));
+        }
+        self.bundled
+            .get(guid)
+            .map(|mapping| (mapping.clone(), MappingSource::Bundled))
+    }
+
+    pub fn user_mappings(&self) -> Vec<String> {
+        self.user
+            .read()
+            .map(|user| user.to_lines())
+            .unwrap_or_default()
+    }
+
+    /// 解析并保存一条用户映射，同一 GUID 的旧映射会被替换
+    pub fn set_user_mapping(&self, line: &str) -> Result<ControllerMapping, GamepadError> {
+        let mapping = ControllerMapping::parse(line)?;
+        if !mapping.matches_platform() {
+            return Err(GamepadError::invalid(format!(
+                "Mapping is for platform {}, expected {}",
+                mapping.platform.as_deref().unwrap_or_default(),
+                PLATFORM
+            )));
+        }
+        self.modify(|user| user.insert(mapping.clone()))?;
+        Ok(mapping)
+    }
+
+    pub fn remove_user_mapping(&self, guid: &str) -> Result<(), GamepadError> {
+        self.modify(|user| {
+            user.remove(guid);
+        })
+    }
+
+    fn modify(&self, f: impl FnOnce(&mut MappingDb)) -> Result<(), GamepadError> {
+        let mut user = self
+            .user
+            .write()
+            .map_err(|_| GamepadError::lock("mappings"))?;
+        f(&mut user);
+        self.save(&user)
+    }
+
+    fn save(&self, user: &MappingDb) -> Result<(), GamepadError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut content = user.to_lines().join("\n");
+        content.push('\n');
+        fs::write(path, content).map_err(GamepadError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DS4: &str = "030000004c050000cc09000011810000,PS4 Controller,a:b0,b:b1,back:b8,\
+dpdown:h0.4,dpleft:h0.8,dpright:h0.2,dpup:h0.1,guide:b10,leftshoulder:b4,leftstick:b11,\
+lefttrigger:a2,leftx:a0,lefty:a1,rightshoulder:b5,rightstick:b12,righttrigger:a5,rightx:a3,\
+righty:a4,start:b9,x:b3,y:b2,platform:Linux,";
+
+    fn raw() -> RawJoystick {
+        RawJoystick {
+            buttons: vec![false; 13],
+            axes: vec![0, 0, i16::MIN, 0, 0, i16::MIN],
+            hats: vec![0],
+        }
+    }
+
+    #[test]
+    fn parse_round_trips() {
+        let mapping = ControllerMapping::parse(DS4).unwrap();
+        assert_eq!(mapping.guid, "030000004c050000cc09000011810000");
+        assert_eq!(mapping.name, "PS4 Controller");
+        assert_eq!(mapping.platform.as_deref(), Some("Linux"));
+        assert_eq!(mapping.bindings.len(), 21);
+        assert_eq!(mapping.to_sdl_string(), DS4);
+    }
+
+    #[test]
+    fn parse_sources() {
+        let mapping = ControllerMapping::parse(
+            "xinput,Test,lefttrigger:+a2,righttrigger:-a2,lefty:a1~,-leftx:h0.8,misc2:b5,",
+        )
+        .unwrap();
+        let sources: Vec<RawSource> = mapping.bindings.iter().map(|b| b.source).collect();
+        assert_eq!(
+            sources,
+            [
+                RawSource::Axis {
+                    index: 2,
+                    range: AxisRange::Positive,
+                    invert: false
+                },
+                RawSource::Axis {
+                    index: 2,
+                    range: AxisRange::Negative,
+                    invert: false
+                },
+                RawSource::Axis {
+                    index: 1,
+                    range: AxisRange::Full,
+                    invert: true
+                },
+                RawSource::Hat(0, 8),
+            ]
+        );
+        assert_eq!(
+            mapping.bindings[3].target,
+            MappingTarget::Axis(Axes::LeftThumbX, AxisRange::Negative)
+        );
+    }
+
+    #[test]
+    fn parse_rejects_invalid_lines() {
+        assert!(ControllerMapping::parse("1234,Short GUID,a:b0,").is_err());
+        assert!(ControllerMapping::parse("030000004c050000cc09000011810000,,a:b0,").is_err());
+        assert!(ControllerMapping::parse("030000004c050000cc09000011810000,Pad,a:+b0,").is_err());
+        assert!(ControllerMapping::parse("030000004c050000cc09000011810000,Pad,a:b,").is_err());
+        assert!(ControllerMapping::parse("030000004c050000cc09000011810000,Pad,a,").is_err());
+    }
+
+    #[test]
+    fn apply_buttons_and_hats() {
+        let mapping = ControllerMapping::parse(DS4).unwrap();
+        let mut raw = raw();
+        raw.buttons[0] = true;
+        raw.buttons[10] = true;
+        raw.hats[0] = 1 | 2;
+        let state = mapping.apply(&raw);
+        let expected: HashSet<Buttons> = [
+            Buttons::A,
+            Buttons::Guide,
+            Buttons::DPadUp,
+            Buttons::DPadRight,
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(state.pressed, expected);
+    }
+
+    #[test]
+    fn apply_axes_and_triggers() {
+        let mapping = ControllerMapping::parse(DS4).unwrap();
+        let mut raw = raw();
+        assert_eq!(mapping.apply(&raw).triggers, (0, 0));
+
+        // SDL 的 Y 轴向下为正，映射后向上为正
+        raw.axes[0] = i16::MAX;
+        raw.axes[1] = i16::MIN;
+        raw.axes[2] = i16::MAX;
+        raw.axes[4] = i16::MAX;
+        let state = mapping.apply(&raw);
+        assert_eq!(state.thumbs, (i16::MAX, i16::MAX, 0, i16::MIN + 1));
+        assert_eq!(state.triggers, (255, 0));
+    }
+
+    #[test]
+    fn apply_half_axes() {
+        let mapping = ControllerMapping::parse(
+            "xinput,Test,lefttrigger:+a2,righttrigger:-a2,-leftx:b0,+leftx:b1,righty:a1~,",
+        )
+        .unwrap();
+        let mut raw = RawJoystick {
+            buttons: vec![false, true],
+            axes: vec![0, i16::MAX, i16::MAX],
+            hats: Vec::new(),
+        };
+        let state = mapping.apply(&raw);
+        assert_eq!(state.triggers, (255, 0));
+        assert_eq!(state.thumbs.0, i16::MAX);
+        // 反向的 Y 轴：原始值最大时为向上推
+        assert_eq!(state.thumbs.3, i16::MAX);
+
+        raw.axes[2] = i16::MIN;
+        raw.buttons = vec![true, false];
+        let state = mapping.apply(&raw);
+        assert_eq!(state.triggers, (0, 255));
+        assert_eq!(state.thumbs.0, i16::MIN);
+    }
+
+    #[test]
+    fn missing_indices_are_ignored() {
+        let mapping = ControllerMapping::parse(DS4).unwrap();
+        let state = mapping.apply(&RawJoystick::default());
+        assert!(state.pressed.is_empty());
+        assert_eq!(state.thumbs, (0, 0, 0, 0));
+    }
+}
@@ -0,0 +1,379 @@
+use crate::util::input_wrapper::{Axes, Buttons};
+use crate::util::mapping::{
+    AxisRange, Binding, ControllerMapping, MappingTarget, RawJoystick, RawSource,
+};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+const AXIS_THRESHOLD: i32 = 16384; // 轴偏离基准超过半程视为有效输入
+const RELEASE_THRESHOLD: i32 = 8000; // 回到基准附近视为已松开
+
+// 向导依次询问的逻辑输入；摇杆轴按 SDL 约定询问：X 向右、Y 向上推
+const WIZARD_STEPS: [MappingTarget; 22] = [
+    MappingTarget::Button(Buttons::A),
+    MappingTarget::Button(Buttons::B),
+    MappingTarget::Button(Buttons::X),
+    MappingTarget::Button(Buttons::Y),
+    MappingTarget::Button(Buttons::Back),
+    MappingTarget::Button(Buttons::Start),
+    MappingTarget::Button(Buttons::Guide),
+    MappingTarget::Button(Buttons::Share),
+    MappingTarget::Button(Buttons::LeftShoulder),
+    MappingTarget::Button(Buttons::RightShoulder),
+    MappingTarget::Button(Buttons::LeftThumb),
+    MappingTarget::Button(Buttons::RightThumb),
+    MappingTarget::Button(Buttons::DPadUp),
+    MappingTarget::Button(Buttons::DPadDown),
+    MappingTarget::Button(Buttons::DPadLeft),
+    MappingTarget::Button(Buttons::DPadRight),
+    MappingTarget::Button(Buttons::LeftTrigger),
+    MappingTarget::Button(Buttons::RightTrigger),
+    MappingTarget::Axis(Axes::LeftThumbX, AxisRange::Full),
+    MappingTarget::Axis(Axes::LeftThumbY, AxisRange::Full),
+    MappingTarget::Axis(Axes::RightThumbX, AxisRange::Full),
+    MappingTarget::Axis(Axes::RightThumbY, AxisRange::Full),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WizardPhase {
+    /// 等待记录基准状态（所有输入处于静止）
+    Calibrating,
+    WaitingInput,
+    /// 已捕获输入，等待松开后进入下一步
+    WaitingRelease,
+    Done,
+}
+
+/// 发送给前端的向导状态
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WizardStatus {
+    pub phase: WizardPhase,
+    pub step: usize,
+    pub total: usize,
+    /// 当前询问的输入（SDL 名称，如 "a"、"lefty"）
+    pub prompt: Option<String>,
+    /// 上一步捕获的原始输入（SDL 格式，如 "b0"、"h0.1"、"+a2"）
+    pub captured: Option<String>,
+    pub mapping: String,
+}
+
+/// 交互式映射向导：逐个询问逻辑输入，捕获变化的原始输入并生成 SDL 映射
+#[derive(Debug, Clone)]
+pub struct MappingWizard {
+    guid: String,
+    name: String,
+    started: Instant,
+    current: usize,
+    phase: WizardPhase,
+    baseline: Option<RawJoystick>,
+    captured: Option<RawSource>,
+    /// 扳机步骤中先于模拟轴出现的按键，松开前轴没有越过阈值才采用
+    pending: Option<RawSource>,
+    bindings: Vec<Binding>,
+}
+
+impl MappingWizard {
+    pub fn new(guid: &str, name: &str) -> Self {
+        MappingWizard {
+            guid: guid.to_string(),
+            name: name.to_string(),
+            started: Instant::now(),
+            current: 0,
+            phase: WizardPhase::Calibrating,
+            baseline: None,
+            captured: None,
+            pending: None,
+            bindings: Vec::new(),
+        }
+    }
+
+    /// 区分向导会话，后台任务据此判断向导是否已被替换
+    pub fn started(&self) -> Instant {
+        self.started
+    }
+
+    /// 输入一帧原始状态，推进向导
+    pub fn update(&mut self, raw: &RawJoystick) {
+        match self.phase {
+            WizardPhase::Calibrating => {
+                self.baseline = Some(raw.clone());
+                self.phase = WizardPhase::WaitingInput;
+            }
+            WizardPhase::WaitingInput => {
+                let (Some(baseline), Some(target)) =
+                    (&self.baseline, WIZARD_STEPS.get(self.current))
+                else {
+                    return;
+                };
+                let mut source = detect(target, baseline, raw, &self.bindings);
+                // DS4/DualSense 的扳机同时上报按键与轴，按键往往先触发；
+                // 按住时继续等待轴，松开时仍没有轴变化才按数字扳机记录
+                if is_trigger(target) && !matches!(source, Some(RawSource::Axis { .. })) {
+                    if source.is_some() {
+                        self.pending = self.pending.or(source);
+                        return;
+                    }
+                    source = self.pending.take();
+                }
+                let Some(source) = source else {
+                    return;
+                };
+                self.bindings.push(Binding {
+                    target: target.clone(),
+                    source,
+                });
+                self.captured = Some(source);
+                self.phase = WizardPhase::WaitingRelease;
+            }
+            WizardPhase::WaitingRelease => {
+                if self.baseline.as_ref().is_some_and(|b| is_released(b, raw)) {
+                    self.advance();
+                }
+            }
+            WizardPhase::Done => {}
+        }
+    }
+
+    /// 跳过当前输入（设备没有该按键）
+    pub fn skip(&mut self) {
+        if matches!(self.phase, WizardPhase::WaitingInput) {
+            self.captured = None;
+            self.advance();
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.phase == WizardPhase::Done
+    }
+
+    pub fn mapping(&self) -> ControllerMapping {
+        ControllerMapping::new(&self.guid, &self.name, self.bindings.clone())
+    }
+
+    pub fn status(&self) -> WizardStatus {
+        WizardStatus {
+            phase: self.phase,
+            step: self.current,
+            total: WIZARD_STEPS.len(),
+            prompt: WIZARD_STEPS
+                .get(self.current)
+                .filter(|_| !self.is_done())
+                .map(MappingTarget::to_sdl_string),
+            captured: self.captured.map(RawSource::to_sdl_string),
+            mapping: self.mapping().to_sdl_string(),
+        }
+    }
+
+    fn advance(&mut self) {
+        self.current += 1;
+        self.pending = None;
+        self.phase = if self.current >= WIZARD_STEPS.len() {
+            WizardPhase::Done
+        } else {
+            WizardPhase::WaitingInput
+        };
+    }
+}
+
+/// 找出相对基准发生变化且尚未映射的原始输入，并换算成目标需要的形式
+fn detect(
+    target: &MappingTarget,
+    baseline: &RawJoystick,
+    raw: &RawJoystick,
+    bound: &[Binding],
+) -> Option<RawSource> {
+    // 同一原始输入不能映射两次
+    let is_free = |source: &RawSource| !bound.iter().any(|b| same_input(&b.source, source));
+    // 推摇杆时另一轴也会偏移，取偏离基准最大的轴
+    let moved_axis = |to_source: &dyn Fn(usize, i32, i32) -> RawSource| {
+        raw.axes
+            .iter()
+            .zip(&baseline.axes)
+            .enumerate()
+            .map(|(index, (&v, &b))| (index, v as i32, b as i32))
+            .filter(|(_, v, b)| (v - b).abs() > AXIS_THRESHOLD)
+            .map(|(index, v, b)| ((v - b).abs(), to_source(index, v, b)))
+            .filter(|(_, source)| is_free(source))
+            .max_by_key(|(deviation, _)| *deviation)
+            .map(|(_, source)| source)
+    };
+
+    if let MappingTarget::Axis(axis, _) = target {
+        // 摇杆只接受轴输入；SDL 的 Y 轴向下为正，向上推时应减小
+        let expect_positive = matches!(axis, Axes::LeftThumbX | Axes::RightThumbX);
+        return moved_axis(&|index, v, b| RawSource::Axis {
+            index,
+            range: AxisRange::Full,
+            invert: (v > b) != expect_positive,
+        });
+    }
+
+    // 扳机优先取模拟轴
+    if is_trigger(target) {
+        if let Some(source) = moved_axis(&axis_source) {
+            return Some(source);
+        }
+    }
+
+    if let Some(source) = raw
+        .buttons
+        .iter()
+        .zip(&baseline.buttons)
+        .enumerate()
+        .filter(|(_, (&now, &before))| now && !before)
+        .map(|(index, _)| RawSource::Button(index))
+        .find(is_free)
+    {
+        return Some(source);
+    }
+    if let Some(source) = raw
+        .hats
+        .iter()
+        .zip(&baseline.hats)
+        .enumerate()
+        .map(|(index, (&now, &before))| (index, now & !before))
+        .filter(|(_, changed)| *changed != 0)
+        // 只取最低位，斜向按下时也只记录一个方向
+        .map(|(index, mask)| RawSource::Hat(index, mask & mask.wrapping_neg()))
+        .find(is_free)
+    {
+        return Some(source);
+    }
+
+    moved_axis(&axis_source)
+}
+
+fn is_trigger(target: &MappingTarget) -> bool {
+    matches!(
+        target,
+        MappingTarget::Button(Buttons::LeftTrigger | Buttons::RightTrigger)
+    )
+}
+
+/// 静止在一端的轴（扳机）取整个行程，以静止端为 0；静止在中心的轴只取移动的半轴
+fn axis_source(index: usize, v: i32, b: i32) -> RawSource {
+    if b <= -AXIS_THRESHOLD {
+        // 静止在最小值的扳机轴，整个行程都是有效输入
+        RawSource::Axis {
+            index,
+            range: AxisRange::Full,
+            invert: false,
+        }
+    } else if b >= AXIS_THRESHOLD {
+        RawSource::Axis {
+            index,
+            range: AxisRange::Full,
+            invert: true,
+        }
+    } else {
+        RawSource::Axis {
+            index,
+            range: if v > b {
+                AxisRange::Positive
+            } else {
+                AxisRange::Negative
+            },
+            invert: false,
+        }
+    }
+}
+
+// 同一个 HAT 的不同方向、同一个轴的正负半轴可以分别映射
+fn same_input(a: &RawSource, b: &RawSource) -> bool {
+    match (a, b) {
+        (
+            RawSource::Axis {
+                index: i1,
+                range: r1,
+                ..
+            },
+            RawSource::Axis {
+                index: i2,
+                range: r2,
+                ..
+            },
+        ) => i1 == i2 && (*r1 == AxisRange::Full || *r2 == AxisRange::Full || r1 == r2),
+        _ => a == b,
+    }
+}
+
+fn is_released(baseline: &RawJoystick, raw: &RawJoystick) -> bool {
+    raw.buttons == baseline.buttons
+        && raw.hats == baseline.hats
+        && raw
+            .axes
+            .iter()
+            .zip(&baseline.axes)
+            .all(|(&v, &b)| (v as i32 - b as i32).abs() < RELEASE_THRESHOLD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn joystick(axes: &[i16], buttons: &[bool]) -> RawJoystick {
+        RawJoystick {
+            buttons: buttons.to_vec(),
+            axes: axes.to_vec(),
+            hats: vec![0],
+        }
+    }
+
+    #[test]
+    fn prefers_largest_axis_deviation() {
+        let baseline = joystick(&[0, 0, 0, 0], &[]);
+        // 向右推左摇杆时 Y 轴也有偏移
+        let raw = joystick(&[30000, 20000, 0, 0], &[]);
+        let target = MappingTarget::Axis(Axes::LeftThumbX, AxisRange::Full);
+        assert_eq!(
+            detect(&target, &baseline, &raw, &[]),
+            Some(RawSource::Axis {
+                index: 0,
+                range: AxisRange::Full,
+                invert: false,
+            })
+        );
+    }
+
+    #[test]
+    fn skips_bound_sources() {
+        let baseline = joystick(&[0, 0, 0, 0], &[false, false]);
+        let bound = [
+            Binding {
+                target: MappingTarget::Axis(Axes::LeftThumbX, AxisRange::Full),
+                source: RawSource::Axis {
+                    index: 0,
+                    range: AxisRange::Full,
+                    invert: false,
+                },
+            },
+            Binding {
+                target: MappingTarget::Button(Buttons::A),
+                source: RawSource::Button(0),
+            },
+        ];
+
+        let raw = joystick(&[32767, -20000, 0, 0], &[false, false]);
+        let target = MappingTarget::Axis(Axes::LeftThumbY, AxisRange::Full);
+        assert_eq!(
+            detect(&target, &baseline, &raw, &bound),
+            Some(RawSource::Axis {
+                index: 1,
+                range: AxisRange::Full,
+                invert: false,
+            })
+        );
+
+        let raw = joystick(&[0, 0, 0, 0], &[true, true]);
+        let target = MappingTarget::Button(Buttons::B);
+        assert_eq!(
+            detect(&target, &baseline, &raw, &bound),
+            Some(RawSource::Button(1))
+        );
+
+        // 只有已映射的输入变化时不记录
+        let raw = joystick(&[32767, 0, 0, 0], &[true, false]);
+        assert_eq!(detect(&target, &baseline, &raw, &bound), None);
+    }
+}
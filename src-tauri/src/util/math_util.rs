@@ -10,6 +10,12 @@ pub struct MathUtil {
     latest_time: i64,
 }
 
+impl Default for MathUtil {
+    fn default() -> Self {
+        MathUtil::new()
+    }
+}
+
 impl MathUtil {
     pub fn new() -> Self {
         MathUtil {
@@ -21,7 +27,7 @@ impl MathUtil {
         }
     }
 
-    pub fn calc_frequency<T: PartialEq>(&mut self, logs: &Vec<(i64, T)>) -> Option<(f64, f64, f64, f64)> {
+    pub fn calc_frequency<T: PartialEq>(&mut self, logs: &[(i64, T)]) -> Option<(f64, f64, f64, f64)> {
         let mut sum: f64 = 0.0;
         let mut valid_count: u64 = 0;
         logs.windows(2)
@@ -30,11 +36,11 @@ impl MathUtil {
                 let log = &pair[1];
                 let prev_log = &pair[0];
                 // 若前后数据点相同, 判定为重复并移除
-                if &log.1 == &prev_log.1 {
+                if log.1 == prev_log.1 {
                     return;
                 }
                 let delta = (log.0 - prev_log.0) as f64;
-                sum += delta as f64;
+                sum += delta;
                 valid_count += 1;
                 if delta < self.min {
                     self.min = delta;
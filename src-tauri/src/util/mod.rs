@@ -1,3 +1,28 @@
 pub mod math_util;
 pub mod input_wrapper;
-pub mod gamepad_util;
\ No newline at end of file
+pub mod mapping;
+pub mod mapping_wizard;
+pub mod gamepad_util;
+pub mod error;
+#[cfg(target_os = "linux")]
+pub mod evdev_input;
+pub mod profile;
+pub mod sampler;
+pub mod sdl_guid;
+pub mod settings;
+pub mod virtual_input;
+pub mod power;
+pub mod motion;
+pub mod touchpad;
+pub mod rumble;
+pub mod led;
+pub mod capabilities;
+pub mod normalize;
+pub mod calibration;
+pub mod transform;
+pub mod gate;
+pub mod snapback;
+pub mod coupling;
+pub mod spectrum;
+pub mod chatter;
+pub mod health;
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+const MICROSECOND: f64 = 1000000.0;
+// 陀螺仪标准差低于该值 (deg/s) 视为静止，此时噪声与零漂统计才有意义
+const STATIONARY_GYRO_STD: f64 = 1.0;
+
+/// 一次 IMU 上报：加速度 (g) 与角速度 (deg/s)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct MotionSample {
+    /// 相对测试开始的时间 (us)
+    pub timestamp: u64,
+    pub accel: (f64, f64, f64),
+    pub gyro: (f64, f64, f64),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MotionStats {
+    pub sample_count: usize,
+    pub polling_rate_avg: f64,
+    pub polling_rate_min: f64,
+    pub polling_rate_max: f64,
+    /// 各轴标准差
+    pub accel_noise: (f64, f64, f64),
+    pub gyro_noise: (f64, f64, f64),
+    /// 各轴角速度均值，静止时即为零漂 (deg/s)
+    pub gyro_drift: (f64, f64, f64),
+    /// 记录期间手柄是否静止；否则噪声与零漂包含真实运动
+    pub stationary: bool,
+}
+
+impl Default for MotionStats {
+    fn default() -> Self {
+        MotionStats::new()
+    }
+}
+
+impl MotionStats {
+    pub fn new() -> Self {
+        MotionStats {
+            sample_count: 0,
+            polling_rate_avg: 0.0,
+            polling_rate_min: 0.0,
+            polling_rate_max: 0.0,
+            accel_noise: (0.0, 0.0, 0.0),
+            gyro_noise: (0.0, 0.0, 0.0),
+            gyro_drift: (0.0, 0.0, 0.0),
+            stationary: false,
+        }
+    }
+}
+
+/// IMU 上报记录，长度与摇杆日志一致
+#[derive(Debug, Clone)]
+pub struct MotionLog {
+    samples: VecDeque<MotionSample>,
+    log_size: usize,
+}
+
+impl MotionLog {
+    pub fn new(log_size: usize) -> Self {
+        MotionLog {
+            samples: VecDeque::with_capacity(log_size),
+            log_size,
+        }
+    }
+
+    pub fn push(&mut self, sample: MotionSample) {
+        while self.samples.len() >= self.log_size.max(1) {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn set_log_size(&mut self, log_size: usize) {
+        self.log_size = log_size;
+        while self.samples.len() > log_size {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn last(&self) -> Option<MotionSample> {
+        self.samples.back().copied()
+    }
+
+    pub fn samples(&self) -> Vec<MotionSample> {
+        self.samples.iter().copied().collect()
+    }
+
+    pub fn stats(&self) -> MotionStats {
+        let mut stats = MotionStats::new();
+        stats.sample_count = self.samples.len();
+        if self.samples.len() < 2 {
+            return stats;
+        }
+
+        let intervals: Vec<u64> = self
+            .samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .map(|(a, b)| b.timestamp.saturating_sub(a.timestamp))
+            .filter(|interval| *interval > 0)
+            .collect();
+        if !intervals.is_empty() {
+            let sum: u64 = intervals.iter().sum();
+            stats.polling_rate_avg = MICROSECOND * intervals.len() as f64 / sum as f64;
+            stats.polling_rate_min = MICROSECOND / *intervals.iter().max().unwrap_or(&1) as f64;
+            stats.polling_rate_max = MICROSECOND / *intervals.iter().min().unwrap_or(&1) as f64;
+        }
+
+        let accel: Vec<(f64, f64, f64)> = self.samples.iter().map(|s| s.accel).collect();
+        let gyro: Vec<(f64, f64, f64)> = self.samples.iter().map(|s| s.gyro).collect();
+        let (_, accel_noise) = mean_std(&accel);
+        let (gyro_mean, gyro_noise) = mean_std(&gyro);
+        stats.accel_noise = accel_noise;
+        stats.gyro_noise = gyro_noise;
+        stats.gyro_drift = gyro_mean;
+        stats.stationary = gyro_noise.0.max(gyro_noise.1).max(gyro_noise.2) < STATIONARY_GYRO_STD;
+        stats
+    }
+}
+
+fn mean_std(values: &[(f64, f64, f64)]) -> ((f64, f64, f64), (f64, f64, f64)) {
+    let n = values.len().max(1) as f64;
+    let mean = values.iter().fold((0.0, 0.0, 0.0), |acc, v| {
+        (acc.0 + v.0 / n, acc.1 + v.1 / n, acc.2 + v.2 / n)
+    });
+    let var = values.iter().fold((0.0, 0.0, 0.0), |acc, v| {
+        (
+            acc.0 + (v.0 - mean.0).powi(2) / n,
+            acc.1 + (v.1 - mean.1).powi(2) / n,
+            acc.2 + (v.2 - mean.2).powi(2) / n,
+        )
+    });
+    (mean, (var.0.sqrt(), var.1.sqrt(), var.2.sqrt()))
+}
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+/// 轴的原生取值范围，对应 evdev 的 input_absinfo
+///
+/// flat 与 fuzz 只保留供分析参考，归一化时不使用；死区由变换设置处理
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NativeRange {
+    pub min: i32,
+    pub max: i32,
+    /// 驱动声明的中心死区（原生单位）
+    pub flat: i32,
+    pub fuzz: i32,
+    /// 原生方向与 XInput 相反（evdev 的 Y 轴向下为正）
+    pub inverted: bool,
+}
+
+impl Default for NativeRange {
+    fn default() -> Self {
+        NativeRange::I16
+    }
+}
+
+impl NativeRange {
+    /// XInput 等直接上报有符号 16 位的设备
+    pub const I16: NativeRange = NativeRange {
+        min: i16::MIN as i32,
+        max: i16::MAX as i32,
+        flat: 0,
+        fuzz: 0,
+        inverted: false,
+    };
+
+    /// 中心值 (min + max) / 2，取值个数为偶数时落在两个值之间（0..=255 为 127.5）；
+    /// 有符号的补码范围（-32768..=32767）按惯例以 0 为中心
+    pub fn center(&self) -> f64 {
+        if self.min == -self.max - 1 {
+            0.0
+        } else {
+            (self.min as f64 + self.max as f64) / 2.0
+        }
+    }
+
+    /// 归一化到 [-1, 1]：两侧分别按各自跨度缩放，使 min、max 恰好对应 -1、1，中心对应 0
+    pub fn normalize(&self, raw: i32) -> f64 {
+        if self.max <= self.min {
+            return 0.0;
+        }
+        let center = self.center();
+        let offset = raw as f64 - center;
+        let span = if offset < 0.0 {
+            center - self.min as f64
+        } else {
+            self.max as f64 - center
+        };
+        if span <= 0.0 {
+            return 0.0;
+        }
+        let value = (offset / span).clamp(-1.0, 1.0);
+        if self.inverted && value != 0.0 {
+            -value
+        } else {
+            value
+        }
+    }
+}
+
+/// 有符号 16 位值归一化到 [-1, 1]，-32768 对应 -1 而不会越界
+pub fn normalize_i16(value: i16) -> f64 {
+    NativeRange::I16.normalize(value as i32)
+}
+
+/// normalize_i16 的逆运算，将 [-1, 1] 还原为有符号 16 位值
+#[cfg(any(test, target_os = "linux"))]
+pub fn denormalize_i16(value: f64) -> i16 {
+    let value = value.clamp(-1.0, 1.0);
+    let span = if value < 0.0 {
+        -(i16::MIN as f64)
+    } else {
+        i16::MAX as f64
+    };
+    (value * span).round() as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(min: i32, max: i32, flat: i32, inverted: bool) -> NativeRange {
+        NativeRange {
+            min,
+            max,
+            flat,
+            fuzz: 0,
+            inverted,
+        }
+    }
+
+    #[test]
+    fn i16_reaches_both_ends() {
+        assert_eq!(normalize_i16(i16::MIN), -1.0);
+        assert_eq!(normalize_i16(i16::MAX), 1.0);
+        assert_eq!(normalize_i16(0), 0.0);
+    }
+
+    #[test]
+    fn unsigned_range_is_centred() {
+        let range = range(0, 255, 0, false);
+        assert_eq!(range.center(), 127.5);
+        assert_eq!(range.normalize(0), -1.0);
+        assert_eq!(range.normalize(255), 1.0);
+        // 中心两侧的取值对称
+        assert_eq!(range.normalize(128), -range.normalize(127));
+        assert!((range.normalize(128) - 0.5 / 127.5).abs() < 1e-12);
+        // 越界的上报被截断
+        assert_eq!(range.normalize(300), 1.0);
+    }
+
+    #[test]
+    fn flat_is_not_applied() {
+        let range = range(-32768, 32767, 4096, false);
+        assert_eq!(range.center(), 0.0);
+        assert_eq!(range.normalize(4096), 4096.0 / 32767.0);
+        assert_eq!(range.normalize(-4096), -4096.0 / 32768.0);
+        assert_eq!(range.normalize(32767), 1.0);
+        assert_eq!(range.normalize(-32768), -1.0);
+    }
+
+    #[test]
+    fn i16_round_trip() {
+        for v in [i16::MIN, -12345, -1, 0, 1, 12345, i16::MAX] {
+            assert_eq!(denormalize_i16(normalize_i16(v)), v);
+        }
+        // 日志中的 i16 与归一化值一致
+        let range = range(0, 255, 0, false);
+        let logged = denormalize_i16(range.normalize(128));
+        assert!((normalize_i16(logged) - range.normalize(128)).abs() < 1.0 / 32767.0);
+    }
+
+    #[test]
+    fn inverted_range_flips_sign() {
+        let range = range(0, 1024, 16, true);
+        assert_eq!(range.normalize(0), 1.0);
+        assert_eq!(range.normalize(1024), -1.0);
+        assert_eq!(range.normalize(512), 0.0);
+    }
+
+    #[test]
+    fn empty_range_is_zero() {
+        assert_eq!(range(10, 10, 0, false).normalize(10), 0.0);
+    }
+}
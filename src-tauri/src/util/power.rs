@@ -0,0 +1,288 @@
+#[cfg(target_os = "linux")]
+use crate::util::sdl_guid::{BUS_BLUETOOTH, BUS_USB};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(target_os = "linux")]
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const HISTORY_SIZE: usize = 1440; // 每分钟一条，保留 24 小时
+const HISTORY_INTERVAL: u64 = 60; // 电量不变时的最小记录间隔 (s)
+const MIN_ESTIMATE_SPAN: u64 = 600; // 至少 10 分钟的放电数据才估算续航
+const SECONDS_PER_HOUR: f64 = 3600.0;
+
+/// 供电方式
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    Wired,
+    Wireless,
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryLevel {
+    Empty,
+    Low,
+    Medium,
+    Full,
+    Unknown,
+}
+
+impl BatteryLevel {
+    pub fn from_percentage(percentage: u8) -> Self {
+        match percentage {
+            0..=10 => BatteryLevel::Empty,
+            11..=40 => BatteryLevel::Low,
+            41..=70 => BatteryLevel::Medium,
+            _ => BatteryLevel::Full,
+        }
+    }
+
+    /// 解析 power_supply 的 capacity_level 属性
+    pub fn from_capacity_level(level: &str) -> Self {
+        match level {
+            "Critical" => BatteryLevel::Empty,
+            "Low" => BatteryLevel::Low,
+            "Normal" => BatteryLevel::Medium,
+            "High" | "Full" => BatteryLevel::Full,
+            _ => BatteryLevel::Unknown,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChargingState {
+    Charging,
+    Discharging,
+    Full,
+    NotCharging,
+    Unknown,
+}
+
+impl ChargingState {
+    /// 解析 power_supply 的 status 属性
+    pub fn from_status(status: &str) -> Self {
+        match status {
+            "Charging" => ChargingState::Charging,
+            "Discharging" => ChargingState::Discharging,
+            "Full" => ChargingState::Full,
+            "Not charging" => ChargingState::NotCharging,
+            _ => ChargingState::Unknown,
+        }
+    }
+}
+
+/// 手柄的供电与电池状态
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PowerInfo {
+    pub source: PowerSource,
+    pub level: BatteryLevel,
+    /// 剩余电量百分比，仅部分驱动提供
+    pub percentage: Option<u8>,
+    pub charging: ChargingState,
+}
+
+impl PowerInfo {
+    pub fn new(source: PowerSource) -> Self {
+        PowerInfo {
+            source,
+            level: BatteryLevel::Unknown,
+            percentage: None,
+            charging: ChargingState::Unknown,
+        }
+    }
+
+    pub fn unknown() -> Self {
+        PowerInfo::new(PowerSource::Unknown)
+    }
+
+    pub fn has_battery(&self) -> bool {
+        self.level != BatteryLevel::Unknown || self.percentage.is_some()
+    }
+}
+
+/// 查找物理设备（如 HID 设备目录）下的电池：<device_dir>/power_supply/*
+#[cfg(target_os = "linux")]
+pub fn find_power_supply(device_dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(device_dir.join("power_supply"))
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| read_attr(path, "type").as_deref() == Some("Battery"))
+}
+
+/// 读取 power_supply 属性；没有电池时只根据总线类型判断供电方式
+#[cfg(target_os = "linux")]
+pub fn read_power_supply(path: Option<&Path>, bus_type: u16) -> PowerInfo {
+    let Some(path) = path else {
+        return PowerInfo::new(match bus_type {
+            BUS_USB => PowerSource::Wired,
+            BUS_BLUETOOTH => PowerSource::Wireless,
+            _ => PowerSource::Unknown,
+        });
+    };
+
+    let percentage = read_attr(path, "capacity")
+        .and_then(|capacity| capacity.parse::<u8>().ok())
+        .map(|capacity| capacity.min(100));
+    let level = match percentage {
+        Some(percentage) => BatteryLevel::from_percentage(percentage),
+        None => read_attr(path, "capacity_level").map_or(BatteryLevel::Unknown, |level| {
+            BatteryLevel::from_capacity_level(&level)
+        }),
+    };
+    let charging = read_attr(path, "status").map_or(ChargingState::Unknown, |status| {
+        ChargingState::from_status(&status)
+    });
+    // USB 接收器连接的无线手柄总线也是 USB，放电中说明没有插线
+    let source = if bus_type == BUS_BLUETOOTH || charging == ChargingState::Discharging {
+        PowerSource::Wireless
+    } else if bus_type == BUS_USB {
+        PowerSource::Wired
+    } else {
+        PowerSource::Unknown
+    };
+
+    PowerInfo {
+        source,
+        level,
+        percentage,
+        charging,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_attr(path: &Path, name: &str) -> Option<String> {
+    fs::read_to_string(path.join(name))
+        .ok()
+        .map(|value| value.trim().to_string())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatterySample {
+    /// Unix 时间 (s)
+    pub timestamp: u64,
+    pub level: BatteryLevel,
+    pub percentage: Option<u8>,
+    pub charging: ChargingState,
+}
+
+/// 发送给前端的电池报告
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatteryReport {
+    pub power: PowerInfo,
+    pub history: Vec<BatterySample>,
+    /// 放电速度 (%/h)
+    pub drain_rate: Option<f64>,
+    /// 按当前放电速度估算的剩余续航 (min)
+    pub estimated_runtime: Option<f64>,
+}
+
+/// 单个设备的电量历史，用于估算续航
+#[derive(Debug, Clone)]
+pub struct BatteryHistory {
+    samples: VecDeque<BatterySample>,
+}
+
+impl Default for BatteryHistory {
+    fn default() -> Self {
+        BatteryHistory::new()
+    }
+}
+
+impl BatteryHistory {
+    pub fn new() -> Self {
+        BatteryHistory {
+            samples: VecDeque::with_capacity(HISTORY_SIZE),
+        }
+    }
+
+    /// 记录一次电量；状态未变化且距上次不足 HISTORY_INTERVAL 时忽略
+    pub fn record(&mut self, power: &PowerInfo) {
+        if !power.has_battery() {
+            return;
+        }
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if let Some(last) = self.samples.back() {
+            let unchanged = last.level == power.level
+                && last.percentage == power.percentage
+                && last.charging == power.charging;
+            if unchanged && timestamp.saturating_sub(last.timestamp) < HISTORY_INTERVAL {
+                return;
+            }
+        }
+        if self.samples.len() == HISTORY_SIZE {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(BatterySample {
+            timestamp,
+            level: power.level,
+            percentage: power.percentage,
+            charging: power.charging,
+        });
+    }
+
+    /// 对最近一段连续放电的百分比做线性回归，得到放电速度 (%/s)
+    ///
+    /// 只有电量等级（如 XInput）时精度不足，不做估算
+    fn drain_per_second(&self) -> Option<f64> {
+        let segment: Vec<(f64, f64)> = self
+            .samples
+            .iter()
+            .rev()
+            .take_while(|sample| {
+                !matches!(
+                    sample.charging,
+                    ChargingState::Charging | ChargingState::Full
+                )
+            })
+            .map_while(|sample| Some((sample.timestamp as f64, sample.percentage? as f64)))
+            .collect();
+        let (first, last) = (segment.last()?, segment.first()?);
+        if segment.len() < 2 || (last.0 - first.0) < MIN_ESTIMATE_SPAN as f64 {
+            return None;
+        }
+
+        let n = segment.len() as f64;
+        let mean_t = segment.iter().map(|(t, _)| t).sum::<f64>() / n;
+        let mean_p = segment.iter().map(|(_, p)| p).sum::<f64>() / n;
+        let (cov, var) = segment.iter().fold((0.0, 0.0), |(cov, var), (t, p)| {
+            (
+                cov + (t - mean_t) * (p - mean_p),
+                var + (t - mean_t).powi(2),
+            )
+        });
+        if var == 0.0 {
+            return None;
+        }
+        let drain = -cov / var;
+        if drain > 0.0 {
+            Some(drain)
+        } else {
+            None
+        }
+    }
+
+    pub fn report(&self, power: PowerInfo) -> BatteryReport {
+        let drain = self.drain_per_second();
+        let percentage = power
+            .percentage
+            .or_else(|| self.samples.back().and_then(|sample| sample.percentage));
+        BatteryReport {
+            power,
+            history: self.samples.iter().copied().collect(),
+            drain_rate: drain.map(|drain| drain * SECONDS_PER_HOUR),
+            estimated_runtime: drain
+                .zip(percentage)
+                .map(|(drain, percentage)| percentage as f64 / drain / 60.0),
+        }
+    }
+}
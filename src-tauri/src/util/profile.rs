@@ -0,0 +1,385 @@
+use crate::util::error::GamepadError;
+use crate::util::gamepad_util::{GamepadInfo, PollingRateResult};
+use crate::util::input_wrapper::{Axes, Buttons, Gamepad};
+use crate::util::transform::TransformSettings;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const PROFILES_FILE: &str = "profiles.json";
+
+/// 单轴校准：中心偏移与缩放，作用于归一化值（与回报率日志使用同一中心）
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AxisCalibration {
+    pub offset: f64,
+    pub scale: f64,
+}
+
+impl AxisCalibration {
+    pub fn apply(&self, value: f64) -> f64 {
+        ((value - self.offset) * self.scale).clamp(-1.0, 1.0)
+    }
+}
+
+impl Default for AxisCalibration {
+    fn default() -> Self {
+        AxisCalibration {
+            offset: 0.0,
+            scale: 1.0,
+        }
+    }
+}
+
+/// 最近一次测试结果
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProfileTestResult {
+    /// unix 时间戳 (s)
+    pub timestamp: u64,
+    pub polling_rate: PollingRateResult,
+}
+
+/// 按设备身份保存的手柄配置，重新连接时自动应用
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct GamepadProfile {
+    pub key: String,
+    pub vendor_id: Option<u16>,
+    pub product_id: Option<u16>,
+    /// 设备上报的名称，用于列表展示
+    pub device_name: String,
+    pub custom_name: Option<String>,
+    /// 键为轴名称，如 "LeftThumbX"
+    pub calibration: HashMap<String, AxisCalibration>,
+    /// 是否在输出中附带校准后的值
+    pub calibration_enabled: bool,
+    /// 死区与响应曲线，启用时在输出中附带变换后的值
+    pub transform: TransformSettings,
+    pub transform_enabled: bool,
+    /// 映射向导生成的 SDL 映射
+    pub mapping: Option<String>,
+    pub last_result: Option<ProfileTestResult>,
+}
+
+impl GamepadProfile {
+    pub fn new(info: &GamepadInfo) -> Self {
+        GamepadProfile {
+            key: info.profile_key.clone(),
+            vendor_id: info.vendor_id,
+            product_id: info.product_id,
+            device_name: info.name.clone(),
+            ..GamepadProfile::default()
+        }
+    }
+
+    /// 启用校准时为各轴填写校准后的值，原始值与归一化值保持不变
+    pub fn apply_calibration(&self, info: &mut GamepadInfo) {
+        if !self.calibration_enabled {
+            return;
+        }
+        for (name, axis) in info.axes.iter_mut() {
+            if let Some(calibration) = self.calibration.get(name) {
+                axis.calibrated = Some(calibration.apply(axis.value));
+            }
+        }
+    }
+
+    /// 启用变换时以校准后的值（没有则为归一化值）为输入，填写各摇杆与扳机变换后的值
+    pub fn apply_transform(&self, info: &mut GamepadInfo) {
+        if !self.transform_enabled {
+            return;
+        }
+        let sticks = [
+            (
+                Axes::LeftThumbX,
+                Axes::LeftThumbY,
+                &self.transform.left_stick,
+            ),
+            (
+                Axes::RightThumbX,
+                Axes::RightThumbY,
+                &self.transform.right_stick,
+            ),
+        ];
+        for (x_axis, y_axis, transform) in sticks {
+            let (x_key, y_key) = (x_axis.to_string(), y_axis.to_string());
+            let input = |key: &str| {
+                info.axes
+                    .get(key)
+                    .map(|axis| axis.calibrated.unwrap_or(axis.value))
+            };
+            let (Some(x), Some(y)) = (input(&x_key), input(&y_key)) else {
+                continue;
+            };
+            let (x, y) = transform.apply(x, y);
+            for (key, value) in [(x_key, x), (y_key, y)] {
+                if let Some(axis) = info.axes.get_mut(&key) {
+                    axis.transformed = Some(value);
+                }
+            }
+        }
+        let triggers = [
+            (Buttons::LeftTrigger, &self.transform.left_trigger),
+            (Buttons::RightTrigger, &self.transform.right_trigger),
+        ];
+        for (button, transform) in triggers {
+            if let Some(trigger) = info.buttons.get_mut(&button.to_string()) {
+                trigger.transformed = Some(transform.apply(trigger.value));
+            }
+        }
+    }
+}
+
+/// 设备身份键：有 VID/PID 时使用总线/VID/PID/版本（及序列号），否则退化为名称和槽位
+pub fn profile_key(gamepad: &Gamepad) -> String {
+    match (gamepad.vendor_id, gamepad.product_id) {
+        (Some(vendor_id), Some(product_id)) => {
+            let mut key = format!(
+                "{:04x}:{:04x}:{:04x}:{:04x}",
+                gamepad.bus_type.unwrap_or(0),
+                vendor_id,
+                product_id,
+                gamepad.version.unwrap_or(0)
+            );
+            if let Some(serial) = gamepad.serial.as_ref().filter(|s| !s.is_empty()) {
+                key.push(':');
+                key.push_str(serial);
+            }
+            key
+        }
+        _ => format!("{}:{}", gamepad.name, gamepad.id),
+    }
+}
+
+/// 配置文件无法完整读取时的情况；下次保存会覆盖原文件，因此先备份
+#[derive(Serialize, Debug, Clone)]
+pub struct ProfileLoadError {
+    /// 原文件的备份路径，备份失败时为空
+    pub backup: Option<String>,
+    /// 无法解析而被丢弃的配置键，整个文件无法解析时为空
+    pub rejected: Vec<String>,
+    pub error: GamepadError,
+}
+
+#[derive(Debug)]
+pub struct ProfileStore {
+    path: Option<PathBuf>,
+    profiles: RwLock<HashMap<String, GamepadProfile>>,
+    load_error: Option<ProfileLoadError>,
+}
+
+impl ProfileStore {
+    /// 读取配置文件；文件不存在时为空，无法解析的配置逐个丢弃，其余保留
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let (profiles, load_error) = match &path {
+            Some(path) => read_profiles(path),
+            None => (HashMap::new(), None),
+        };
+        ProfileStore {
+            path,
+            profiles: RwLock::new(profiles),
+            load_error,
+        }
+    }
+
+    pub fn load_error(&self) -> Option<ProfileLoadError> {
+        self.load_error.clone()
+    }
+
+    pub fn get(&self, key: &str) -> Option<GamepadProfile> {
+        self.profiles
+            .read()
+            .ok()
+            .and_then(|profiles| profiles.get(key).cloned())
+    }
+
+    pub fn all(&self) -> Vec<GamepadProfile> {
+        let mut profiles: Vec<GamepadProfile> = self
+            .profiles
+            .read()
+            .map(|profiles| profiles.values().cloned().collect())
+            .unwrap_or_default();
+        profiles.sort_by(|a, b| a.key.cmp(&b.key));
+        profiles
+    }
+
+    /// 获取设备的配置，不存在时创建（不立即写入文件）
+    pub fn get_or_create(&self, info: &GamepadInfo) -> GamepadProfile {
+        if let Some(profile) = self.get(&info.profile_key) {
+            return profile;
+        }
+        let profile = GamepadProfile::new(info);
+        if let Ok(mut profiles) = self.profiles.write() {
+            profiles.insert(profile.key.clone(), profile.clone());
+        }
+        profile
+    }
+
+    pub fn update(&self, profile: GamepadProfile) -> Result<(), GamepadError> {
+        if profile.key.is_empty() {
+            return Err(GamepadError::invalid("Profile key must not be empty"));
+        }
+        self.modify(|profiles| {
+            profiles.insert(profile.key.clone(), profile);
+        })
+    }
+
+    pub fn remove(&self, key: &str) -> Result<(), GamepadError> {
+        self.modify(|profiles| {
+            profiles.remove(key);
+        })
+    }
+
+    /// 保存最近一次测试结果
+    pub fn record_result(
+        &self,
+        key: &str,
+        polling_rate: PollingRateResult,
+    ) -> Result<(), GamepadError> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        self.modify(|profiles| {
+            if let Some(profile) = profiles.get_mut(key) {
+                profile.last_result = Some(ProfileTestResult {
+                    timestamp,
+                    polling_rate,
+                });
+            }
+        })
+    }
+
+    fn modify(
+        &self,
+        f: impl FnOnce(&mut HashMap<String, GamepadProfile>),
+    ) -> Result<(), GamepadError> {
+        let mut profiles = self
+            .profiles
+            .write()
+            .map_err(|_| GamepadError::lock("profiles"))?;
+        f(&mut profiles);
+        self.save(&profiles)
+    }
+
+    fn save(&self, profiles: &HashMap<String, GamepadProfile>) -> Result<(), GamepadError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content = serde_json::to_string_pretty(profiles)?;
+        fs::write(path, content).map_err(GamepadError::from)
+    }
+}
+
+fn read_profiles(path: &Path) -> (HashMap<String, GamepadProfile>, Option<ProfileLoadError>) {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return (HashMap::new(), None),
+        Err(e) => {
+            let error = ProfileLoadError {
+                backup: None,
+                rejected: Vec::new(),
+                error: e.into(),
+            };
+            return (HashMap::new(), Some(error));
+        }
+    };
+    let (profiles, rejected, error) = parse_profiles(&content);
+    let Some(error) = error else {
+        return (profiles, None);
+    };
+    let backup = path.with_extension("json.bak");
+    let backup = fs::write(&backup, &content)
+        .ok()
+        .map(|_| backup.to_string_lossy().into_owned());
+    let error = ProfileLoadError {
+        backup,
+        rejected,
+        error,
+    };
+    (profiles, Some(error))
+}
+
+/// 逐个解析配置，返回可用的配置、被丢弃的键与第一个错误
+fn parse_profiles(
+    content: &str,
+) -> (
+    HashMap<String, GamepadProfile>,
+    Vec<String>,
+    Option<GamepadError>,
+) {
+    let entries = match serde_json::from_str::<HashMap<String, Value>>(content) {
+        Ok(entries) => entries,
+        Err(e) => return (HashMap::new(), Vec::new(), Some(e.into())),
+    };
+    let mut profiles = HashMap::new();
+    let mut rejected = Vec::new();
+    let mut error = None;
+    for (key, entry) in entries {
+        match serde_json::from_value::<GamepadProfile>(entry) {
+            Ok(profile) => {
+                profiles.insert(key, profile);
+            }
+            Err(e) => {
+                error.get_or_insert_with(|| {
+                    GamepadError::InvalidData(format!("Profile {}: {}", key, e))
+                });
+                rejected.push(key);
+            }
+        }
+    }
+    rejected.sort();
+    (profiles, rejected, error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_profiles_are_dropped_individually() {
+        let content = r#"{
+            "good": { "key": "good", "device_name": "Pad" },
+            "bad": { "key": "bad", "calibration_enabled": "yes" }
+        }"#;
+        let (profiles, rejected, error) = parse_profiles(content);
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles["good"].device_name, "Pad");
+        assert_eq!(rejected, ["bad"]);
+        assert!(matches!(error, Some(GamepadError::InvalidData(_))));
+    }
+
+    #[test]
+    fn unreadable_file_is_reported() {
+        let (profiles, rejected, error) = parse_profiles("{ not json");
+        assert!(profiles.is_empty());
+        assert!(rejected.is_empty());
+        assert!(matches!(error, Some(GamepadError::InvalidData(_))));
+
+        let (_, _, error) = parse_profiles("{}");
+        assert!(error.is_none());
+    }
+
+    #[test]
+    fn broken_file_is_backed_up() {
+        let dir = std::env::temp_dir().join(format!("profile-load-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(PROFILES_FILE);
+        fs::write(&path, "{ not json").unwrap();
+
+        let store = ProfileStore::load(Some(path.clone()));
+        let error = store.load_error().unwrap();
+        let backup = error.backup.unwrap();
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "{ not json");
+        assert!(store.all().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,185 @@
+use crate::util::error::GamepadError;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const MAX_PATTERN_DURATION: u64 = 60000; // ms, 单个序列的最长时长
+const MAX_REPEAT: u32 = 100;
+const RAMP_STEP: u64 = 20; // ms, 渐变按该间隔离散为恒定强度段
+
+/// 震动序列中的一步，强度范围 [0, 1]，时长单位 ms
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RumbleStep {
+    Constant {
+        low_frequency: f64,
+        high_frequency: f64,
+        duration: u64,
+    },
+    /// 从 from 线性渐变到 to，元组为 (低频, 高频)
+    Ramp {
+        from: (f64, f64),
+        to: (f64, f64),
+        duration: u64,
+    },
+    /// 以 on/off 间隔重复 count 次
+    Pulse {
+        low_frequency: f64,
+        high_frequency: f64,
+        on: u64,
+        off: u64,
+        count: u32,
+    },
+    Pause {
+        duration: u64,
+    },
+}
+
+impl RumbleStep {
+    /// 时长来自前端，溢出时返回 None
+    fn duration(&self) -> Option<u64> {
+        match self {
+            RumbleStep::Constant { duration, .. }
+            | RumbleStep::Ramp { duration, .. }
+            | RumbleStep::Pause { duration } => Some(*duration),
+            RumbleStep::Pulse { on, off, count, .. } => {
+                on.checked_add(*off)?.checked_mul(*count as u64)
+            }
+        }
+    }
+
+    fn strengths(&self) -> Vec<f64> {
+        match self {
+            RumbleStep::Constant {
+                low_frequency,
+                high_frequency,
+                ..
+            }
+            | RumbleStep::Pulse {
+                low_frequency,
+                high_frequency,
+                ..
+            } => vec![*low_frequency, *high_frequency],
+            RumbleStep::Ramp { from, to, .. } => vec![from.0, from.1, to.0, to.1],
+            RumbleStep::Pause { .. } => Vec::new(),
+        }
+    }
+}
+
+/// 展开后的一段恒定强度，马达强度范围 0..=65535
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RumbleSegment {
+    pub low: u16,
+    pub high: u16,
+    pub duration: Duration,
+}
+
+/// 震动序列，整体重复 repeat 次（0 与 1 均为播放一次）
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RumblePattern {
+    pub steps: Vec<RumbleStep>,
+    #[serde(default)]
+    pub repeat: u32,
+}
+
+impl RumblePattern {
+    pub fn constant(low_frequency: f64, high_frequency: f64, duration: u64) -> Self {
+        RumblePattern {
+            steps: vec![RumbleStep::Constant {
+                low_frequency,
+                high_frequency,
+                duration,
+            }],
+            repeat: 1,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), GamepadError> {
+        if self.steps.is_empty() {
+            return Err(GamepadError::invalid("Rumble pattern has no steps"));
+        }
+        if self.repeat > MAX_REPEAT {
+            return Err(GamepadError::invalid(format!(
+                "Rumble repeat must be within 0..={}, got {}",
+                MAX_REPEAT, self.repeat
+            )));
+        }
+        if let Some(strength) = self
+            .steps
+            .iter()
+            .flat_map(RumbleStep::strengths)
+            .find(|v| !(0.0..=1.0).contains(v))
+        {
+            return Err(GamepadError::invalid(format!(
+                "Rumble strength must be within 0..=1, got {}",
+                strength
+            )));
+        }
+        let total = self
+            .steps
+            .iter()
+            .try_fold(0u64, |total, step| total.checked_add(step.duration()?))
+            .and_then(|total| total.checked_mul(self.repeat.max(1) as u64))
+            .ok_or_else(|| GamepadError::invalid("Rumble pattern duration overflows"))?;
+        if total == 0 || total > MAX_PATTERN_DURATION {
+            return Err(GamepadError::invalid(format!(
+                "Rumble pattern duration must be within 1..={} ms, got {}",
+                MAX_PATTERN_DURATION, total
+            )));
+        }
+        Ok(())
+    }
+
+    /// 将序列展开为恒定强度段，播放时逐段设置马达
+    pub fn segments(&self) -> Vec<RumbleSegment> {
+        let mut segments = Vec::new();
+        for _ in 0..self.repeat.max(1) {
+            for step in &self.steps {
+                match *step {
+                    RumbleStep::Constant {
+                        low_frequency,
+                        high_frequency,
+                        duration,
+                    } => segments.push(segment(low_frequency, high_frequency, duration)),
+                    RumbleStep::Ramp { from, to, duration } => {
+                        let count = duration.div_ceil(RAMP_STEP).max(1);
+                        for i in 0..count {
+                            let start = i * RAMP_STEP;
+                            let length = RAMP_STEP.min(duration - start.min(duration));
+                            // 取每段中点的强度
+                            let t = (start as f64 + length as f64 / 2.0) / duration.max(1) as f64;
+                            segments.push(segment(
+                                from.0 + (to.0 - from.0) * t,
+                                from.1 + (to.1 - from.1) * t,
+                                length,
+                            ));
+                        }
+                    }
+                    RumbleStep::Pulse {
+                        low_frequency,
+                        high_frequency,
+                        on,
+                        off,
+                        count,
+                    } => {
+                        for _ in 0..count {
+                            segments.push(segment(low_frequency, high_frequency, on));
+                            segments.push(segment(0.0, 0.0, off));
+                        }
+                    }
+                    RumbleStep::Pause { duration } => segments.push(segment(0.0, 0.0, duration)),
+                }
+            }
+        }
+        segments.retain(|segment| !segment.duration.is_zero());
+        segments
+    }
+}
+
+fn segment(low_frequency: f64, high_frequency: f64, duration: u64) -> RumbleSegment {
+    let strength = |v: f64| (v.clamp(0.0, 1.0) * u16::MAX as f64).round() as u16;
+    RumbleSegment {
+        low: strength(low_frequency),
+        high: strength(high_frequency),
+        duration: Duration::from_millis(duration),
+    }
+}
@@ -0,0 +1,309 @@
+use crate::util::error::GamepadError;
+use crate::util::input_wrapper::RawInput;
+use crate::util::math_util::MathUtil;
+use crate::util::virtual_input::VirtualInput;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::thread;
+use std::time::{Duration, Instant};
+#[cfg(target_os = "linux")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const MICROSECOND: f64 = 1000000.0;
+const DEFAULT_TARGET_RATE: u32 = 4000; // 250us
+const MAX_TARGET_RATE: u32 = 20000;
+const DEFAULT_SPIN_TAIL_MICROSECONDS: u64 = 1000;
+const STATS_WINDOW: usize = 2000; // 统计最近 2000 次采样
+
+/// 采样等待策略
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SamplerStrategy {
+    /// 直接 thread::sleep，CPU 占用低，受系统调度粒度影响
+    Sleep,
+    /// 忙等待（yield），精度高，占用一个核心
+    Spin,
+    /// 先 sleep，最后 spin_tail 微秒改为忙等待
+    Hybrid,
+    /// 阻塞读取设备事件，使用内核时间戳（仅 evdev）
+    Event,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SamplerConfig {
+    /// 目标采样率 (Hz)
+    pub target_rate: u32,
+    pub strategy: SamplerStrategy,
+    /// Hybrid 策略下的忙等待时长 (us)
+    pub spin_tail: u64,
+}
+
+impl Default for SamplerConfig {
+    fn default() -> Self {
+        // Windows 下 sleep 精度约 1ms，默认忙等待；其他平台保持 sleep
+        #[cfg(target_os = "windows")]
+        let strategy = SamplerStrategy::Spin;
+        #[cfg(not(target_os = "windows"))]
+        let strategy = SamplerStrategy::Sleep;
+
+        SamplerConfig {
+            target_rate: DEFAULT_TARGET_RATE,
+            strategy,
+            spin_tail: DEFAULT_SPIN_TAIL_MICROSECONDS,
+        }
+    }
+}
+
+impl SamplerConfig {
+    pub fn validate(&self) -> Result<(), GamepadError> {
+        if self.target_rate == 0 || self.target_rate > MAX_TARGET_RATE {
+            return Err(GamepadError::invalid(format!(
+                "Sampler target rate must be within 1..={} Hz, got {}",
+                MAX_TARGET_RATE, self.target_rate
+            )));
+        }
+        #[cfg(not(target_os = "linux"))]
+        if self.strategy == SamplerStrategy::Event {
+            return Err(GamepadError::BackendUnavailable(
+                "Event sampling is not supported by this backend".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn period(&self) -> Duration {
+        Duration::from_micros(1_000_000 / self.target_rate.max(1) as u64)
+    }
+}
+
+/// 采样器自身的实际表现，用于判断测得的回报率是否受本工具限制
+#[derive(Serialize, Debug, Deserialize, Clone)]
+pub struct SamplerStats {
+    pub target_rate: f64,
+    /// 实际达到的采样率 (Hz)
+    pub achieved_rate: f64,
+    /// 相对截止时间的平均/最大超时 (us)，事件模式下为内核时间戳到读取的延迟
+    pub avg_overshoot: f64,
+    pub max_overshoot: f64,
+    pub sample_count: u64,
+}
+
+impl Default for SamplerStats {
+    fn default() -> Self {
+        SamplerStats::new()
+    }
+}
+
+impl SamplerStats {
+    pub fn new() -> Self {
+        SamplerStats {
+            target_rate: 0.0,
+            achieved_rate: 0.0,
+            avg_overshoot: 0.0,
+            max_overshoot: 0.0,
+            sample_count: 0,
+        }
+    }
+}
+
+/// 采样器对已知虚拟设备的测量误差
+#[derive(Serialize, Debug, Deserialize, Clone)]
+pub struct SamplerCalibration {
+    /// 虚拟设备的真实回报率 (Hz) 与抖动 (us)
+    pub report_rate: f64,
+    pub jitter: u64,
+    /// 本工具测得的回报率 (Hz) 及相对误差
+    pub measured_rate: f64,
+    pub rate_error: f64,
+    /// 上报发生到被采样到的延迟 (us)
+    pub avg_timing_error: f64,
+    pub max_timing_error: f64,
+    /// 两次采样之间被覆盖、未能观测到的上报数
+    pub missed_reports: u64,
+    pub sampler: SamplerStats,
+}
+
+#[derive(Debug)]
+pub struct Sampler {
+    config: SamplerConfig,
+    period: Duration,
+    start: Instant,
+    deadline: Option<Instant>,
+    last_tick: Option<u64>,
+    intervals: VecDeque<u64>,
+    overshoots: VecDeque<u64>,
+    sample_count: u64,
+}
+
+impl Sampler {
+    pub fn new(config: SamplerConfig) -> Self {
+        let period = config.period();
+        Sampler {
+            config,
+            period,
+            start: Instant::now(),
+            deadline: None,
+            last_tick: None,
+            intervals: VecDeque::with_capacity(STATS_WINDOW),
+            overshoots: VecDeque::with_capacity(STATS_WINDOW),
+            sample_count: 0,
+        }
+    }
+
+    /// 丢弃时间基准（例如设备断开后待机），下一次 wait 重新对齐
+    pub fn resync(&mut self) {
+        self.deadline = None;
+        self.last_tick = None;
+    }
+
+    /// 等待到下一个采样时刻并记录本次的间隔与超时
+    pub fn wait(&mut self) {
+        let now = Instant::now();
+        let deadline = match self.deadline {
+            Some(deadline) => deadline,
+            None => now + self.period,
+        };
+
+        self.wait_until(deadline);
+
+        let tick = Instant::now();
+        let overshoot = tick.saturating_duration_since(deadline).as_micros() as u64;
+        self.record_tick((tick - self.start).as_micros() as u64, overshoot);
+
+        // 落后超过一个周期时不补采，直接以当前时刻为基准
+        let next = deadline + self.period;
+        self.deadline = Some(if next <= tick {
+            tick + self.period
+        } else {
+            next
+        });
+    }
+
+    /// 事件驱动模式下记录一次上报，间隔取自内核时间戳
+    #[cfg(target_os = "linux")]
+    pub fn record_report(&mut self, time: SystemTime) {
+        let latency = SystemTime::now()
+            .duration_since(time)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        let tick = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        self.record_tick(tick, latency);
+    }
+
+    fn record_tick(&mut self, tick: u64, overshoot: u64) {
+        push_bounded(&mut self.overshoots, overshoot);
+        if let Some(last_tick) = self.last_tick {
+            push_bounded(&mut self.intervals, tick.saturating_sub(last_tick));
+        }
+        self.last_tick = Some(tick);
+        self.sample_count += 1;
+    }
+
+    fn wait_until(&self, deadline: Instant) {
+        match self.config.strategy {
+            SamplerStrategy::Sleep | SamplerStrategy::Event => {
+                let now = Instant::now();
+                if deadline > now {
+                    thread::sleep(deadline - now);
+                }
+            }
+            SamplerStrategy::Spin => spin_until(deadline),
+            SamplerStrategy::Hybrid => {
+                let spin_tail = Duration::from_micros(self.config.spin_tail);
+                let now = Instant::now();
+                if deadline > now + spin_tail {
+                    thread::sleep(deadline - now - spin_tail);
+                }
+                spin_until(deadline);
+            }
+        }
+    }
+
+    pub fn stats(&self) -> SamplerStats {
+        let interval_sum: u64 = self.intervals.iter().sum();
+        let achieved_rate = if interval_sum == 0 {
+            0.0
+        } else {
+            MICROSECOND * self.intervals.len() as f64 / interval_sum as f64
+        };
+        let overshoot_n = self.overshoots.len().max(1) as f64;
+        SamplerStats {
+            target_rate: self.config.target_rate as f64,
+            achieved_rate,
+            avg_overshoot: self.overshoots.iter().sum::<u64>() as f64 / overshoot_n,
+            max_overshoot: self.overshoots.iter().max().copied().unwrap_or(0) as f64,
+            sample_count: self.sample_count,
+        }
+    }
+}
+
+fn spin_until(deadline: Instant) {
+    while Instant::now() < deadline {
+        // 使用yield来避免忙等待
+        thread::yield_now();
+    }
+}
+
+fn push_bounded(queue: &mut VecDeque<u64>, val: u64) {
+    if queue.len() == STATS_WINDOW {
+        queue.pop_front();
+    }
+    queue.push_back(val);
+}
+
+/// 用给定采样器配置轮询虚拟设备 duration 时长，对比测量值与真实值
+pub fn calibrate(
+    config: SamplerConfig,
+    report_rate: f64,
+    jitter: u64,
+    duration: Duration,
+) -> SamplerCalibration {
+    let device = VirtualInput::with_config(report_rate, jitter);
+    let mut sampler = Sampler::new(config);
+    let start = Instant::now();
+    let mut logs: Vec<(i64, (i16, i16, i16, i16))> = Vec::new();
+    let mut timing_errors: Vec<u64> = Vec::new();
+    let mut last_count: u64 = 0;
+    let mut missed_reports: u64 = 0;
+
+    while start.elapsed() < duration {
+        if let Ok(state) = device.update(0) {
+            if state.report_count != last_count {
+                let now = Instant::now();
+                logs.push(((now - start).as_micros() as i64, state.thumbs));
+                if let Some(report_time) = state.report_time {
+                    timing_errors
+                        .push(now.saturating_duration_since(report_time).as_micros() as u64);
+                }
+                if last_count > 0 {
+                    missed_reports += state.report_count - last_count - 1;
+                }
+                last_count = state.report_count;
+            }
+        }
+        sampler.wait();
+    }
+
+    let measured_rate = if logs.len() < 2 {
+        0.0
+    } else {
+        MathUtil::new()
+            .calc_frequency(&logs)
+            .map(|result| result.0)
+            .unwrap_or(0.0)
+    };
+    let error_n = timing_errors.len().max(1) as f64;
+    SamplerCalibration {
+        report_rate,
+        jitter,
+        measured_rate,
+        rate_error: (measured_rate - report_rate) / report_rate,
+        avg_timing_error: timing_errors.iter().sum::<u64>() as f64 / error_n,
+        max_timing_error: timing_errors.iter().max().copied().unwrap_or(0) as f64,
+        missed_reports,
+        sampler: sampler.stats(),
+    }
+}
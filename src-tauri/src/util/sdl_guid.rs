@@ -0,0 +1,127 @@
+/// SDL 驱动签名，写入 GUID 第 14 字节
+#[cfg(any(test, not(target_os = "linux")))]
+pub const DRIVER_XINPUT: u8 = b'x';
+pub const DRIVER_VIRTUAL: u8 = b'v';
+
+/// USB 总线，XInput 设备按此处理
+pub const BUS_USB: u16 = 0x03;
+#[cfg(any(test, target_os = "linux"))]
+pub const BUS_BLUETOOTH: u16 = 0x05;
+pub const BUS_VIRTUAL: u16 = 0x00;
+
+/// 按 SDL2 (SDL_CreateJoystickGUID) 的规则生成 128 位 GUID 的十六进制字符串
+///
+/// 布局（小端 u16）：总线、名称 CRC16、VID、0、PID、0、版本、驱动签名/数据；
+/// VID 或 PID 为 0 时，第 4 字节起改为存放设备名称的前 11 个字节
+pub fn create_guid(
+    bus_type: u16,
+    vendor: u16,
+    product: u16,
+    version: u16,
+    name: &str,
+    driver_signature: u8,
+) -> String {
+    let mut data = [0u8; 16];
+    data[0..2].copy_from_slice(&bus_type.to_le_bytes());
+    data[2..4].copy_from_slice(&crc16(0, name.as_bytes()).to_le_bytes());
+
+    if vendor != 0 && product != 0 {
+        data[4..6].copy_from_slice(&vendor.to_le_bytes());
+        data[8..10].copy_from_slice(&product.to_le_bytes());
+        data[12..14].copy_from_slice(&version.to_le_bytes());
+        data[14] = driver_signature;
+    } else {
+        // 与 SDL_strlcpy 一致：保留结尾的 '\0'
+        let mut available = data.len() - 4;
+        if driver_signature != 0 {
+            available -= 2;
+            data[14] = driver_signature;
+        }
+        let bytes = name.as_bytes();
+        let len = bytes.len().min(available - 1);
+        data[4..4 + len].copy_from_slice(&bytes[..len]);
+    }
+
+    data.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 去掉 GUID 中的名称 CRC，SDL 匹配映射数据库时同样忽略该字段
+pub fn strip_crc(guid: &str) -> String {
+    if guid.len() != 32 {
+        return guid.to_string();
+    }
+    format!("{}0000{}", &guid[0..4], &guid[8..])
+}
+
+// SDL_crc16：CRC-16/ARC (多项式 0xA001，反射)
+fn crc16(mut crc: u16, data: &[u8]) -> u16 {
+    for &byte in data {
+        let mut r = (crc as u8) ^ byte;
+        let mut value: u16 = 0;
+        for _ in 0..8 {
+            value = if (value ^ r as u16) & 1 != 0 {
+                0xA001
+            } else {
+                0
+            } ^ (value >> 1);
+            r >>= 1;
+        }
+        crc = value ^ (crc >> 8);
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc16_matches_arc_check_value() {
+        assert_eq!(crc16(0, b"123456789"), 0xBB3D);
+        assert_eq!(crc16(0, b""), 0);
+    }
+
+    #[test]
+    fn guid_matches_gamecontrollerdb() {
+        // 映射数据库中的 GUID 不含名称 CRC
+        let xbox360 = create_guid(BUS_USB, 0x045e, 0x028e, 0x0114, "Xbox 360 Controller", 0);
+        assert_eq!(strip_crc(&xbox360), "030000005e0400008e02000014010000");
+        let ds4 = create_guid(BUS_USB, 0x054c, 0x09cc, 0x8111, "Wireless Controller", 0);
+        assert_eq!(strip_crc(&ds4), "030000004c050000cc09000011810000");
+    }
+
+    #[test]
+    fn guid_contains_name_crc_and_driver_signature() {
+        let name = "XInput Controller 0";
+        let guid = create_guid(BUS_USB, 0x045e, 0x028e, 0, name, DRIVER_XINPUT);
+        let crc = crc16(0, name.as_bytes()).to_le_bytes();
+        assert_eq!(&guid[4..8], format!("{:02x}{:02x}", crc[0], crc[1]));
+        assert_eq!(&guid[28..30], "78");
+    }
+
+    #[test]
+    fn guid_without_ids_stores_name() {
+        let guid = create_guid(BUS_VIRTUAL, 0, 0, 0, "Dummy 0", DRIVER_VIRTUAL);
+        assert_eq!(&guid[0..4], "0000");
+        assert_eq!(&guid[8..22], "44756d6d792030");
+        assert_eq!(&guid[22..28], "000000");
+        assert_eq!(&guid[28..32], "7600");
+
+        // 有驱动签名时名称最多 9 个字节，之后保留 '\0'
+        let guid = create_guid(BUS_BLUETOOTH, 0, 0, 0, "ABCDEFGHIJKLMN", DRIVER_VIRTUAL);
+        assert_eq!(&guid[8..26], "414243444546474849");
+        assert_eq!(&guid[26..28], "00");
+        let guid = create_guid(BUS_BLUETOOTH, 0, 0, 0, "ABCDEFGHIJKLMN", 0);
+        assert_eq!(&guid[8..30], "4142434445464748494a4b");
+        assert_eq!(&guid[30..32], "00");
+    }
+
+    #[test]
+    fn strip_crc_only_clears_crc() {
+        assert_eq!(
+            strip_crc("030012345e0400008e02000014010000"),
+            "030000005e0400008e02000014010000"
+        );
+        assert_eq!(strip_crc("xinput"), "xinput");
+    }
+}
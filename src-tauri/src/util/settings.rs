@@ -0,0 +1,220 @@
+use crate::util::error::GamepadError;
+use crate::util::health::HealthThresholds;
+use crate::util::sampler::SamplerConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+pub const SETTINGS_FILE: &str = "settings.json";
+pub const DEFAULT_LOG_SIZE: usize = 2000;
+const DEFAULT_FRAME_RATE: u64 = 120;
+const MAX_FRAME_RATE: u64 = 1000;
+const MIN_LOG_SIZE: usize = 200; // 至少容纳两次统计间隔
+const MAX_LOG_SIZE: usize = 100000;
+const LANGUAGES: [&str; 2] = ["en", "zh"];
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Settings {
+    pub frame_rate: u64,
+    pub log_size: usize,
+    pub is_record_log: bool,
+    pub language: String,
+    pub sampler: SamplerConfig,
+    /// 健康报告各项指标的阈值
+    pub health: HealthThresholds,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            frame_rate: DEFAULT_FRAME_RATE,
+            log_size: DEFAULT_LOG_SIZE,
+            is_record_log: false,
+            language: LANGUAGES[0].to_string(),
+            sampler: SamplerConfig::default(),
+            health: HealthThresholds::default(),
+        }
+    }
+}
+
+impl Settings {
+    pub fn validate(&self) -> Result<(), GamepadError> {
+        if self.frame_rate == 0 || self.frame_rate > MAX_FRAME_RATE {
+            return Err(GamepadError::invalid(format!(
+                "Frame rate must be within 1..={}, got {}",
+                MAX_FRAME_RATE, self.frame_rate
+            )));
+        }
+        if self.log_size < MIN_LOG_SIZE || self.log_size > MAX_LOG_SIZE {
+            return Err(GamepadError::invalid(format!(
+                "Log size must be within {}..={}, got {}",
+                MIN_LOG_SIZE, MAX_LOG_SIZE, self.log_size
+            )));
+        }
+        if !LANGUAGES.contains(&self.language.as_str()) {
+            return Err(GamepadError::invalid(format!(
+                "Unsupported language: {}",
+                self.language
+            )));
+        }
+        self.sampler.validate()?;
+        self.health.validate()
+    }
+
+    /// 逐个字段读取，类型错误或取值非法的字段使用默认值，其余字段保留；
+    /// 同时返回被忽略的字段
+    fn from_value(value: Value) -> (Self, Vec<RejectedSetting>) {
+        let mut settings = Settings::default();
+        let mut rejected = Vec::new();
+        let Value::Object(fields) = value else {
+            rejected.push(RejectedSetting {
+                key: None,
+                error: GamepadError::InvalidData("Settings must be a JSON object".to_string()),
+            });
+            return (settings, rejected);
+        };
+        for (key, field) in fields {
+            let Ok(Value::Object(mut merged)) = serde_json::to_value(&settings) else {
+                break;
+            };
+            merged.insert(key.clone(), field);
+            // settings 始终合法，合并后校验失败说明是该字段的问题
+            match serde_json::from_value::<Settings>(Value::Object(merged))
+                .map_err(GamepadError::from)
+                .and_then(|candidate| candidate.validate().map(|_| candidate))
+            {
+                Ok(candidate) => settings = candidate,
+                Err(error) => rejected.push(RejectedSetting {
+                    key: Some(key),
+                    error,
+                }),
+            }
+        }
+        (settings, rejected)
+    }
+}
+
+/// 读取设置文件时被忽略而使用默认值的内容
+#[derive(Serialize, Debug, Clone)]
+pub struct RejectedSetting {
+    /// 字段名；整个文件无法解析时为空
+    pub key: Option<String>,
+    pub error: GamepadError,
+}
+
+/// 设置文件的读写，文件位于应用配置目录
+#[derive(Debug)]
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    settings: RwLock<Settings>,
+    /// 读取时被忽略的内容，供前端提示
+    rejected: Vec<RejectedSetting>,
+}
+
+impl SettingsStore {
+    /// 读取设置文件；文件不存在时使用默认值，无法解析的文件或字段使用默认值并记录在 rejected 中
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let content = path.as_ref().and_then(|path| fs::read_to_string(path).ok());
+        let (settings, rejected) = match content.map(|c| serde_json::from_str::<Value>(&c)) {
+            Some(Ok(value)) => Settings::from_value(value),
+            Some(Err(e)) => (
+                Settings::default(),
+                vec![RejectedSetting {
+                    key: None,
+                    error: e.into(),
+                }],
+            ),
+            None => (Settings::default(), Vec::new()),
+        };
+        SettingsStore {
+            path,
+            settings: RwLock::new(settings),
+            rejected,
+        }
+    }
+
+    pub fn rejected(&self) -> Vec<RejectedSetting> {
+        self.rejected.clone()
+    }
+
+    pub fn get(&self) -> Settings {
+        self.settings
+            .read()
+            .map(|settings| settings.clone())
+            .unwrap_or_default()
+    }
+
+    /// 校验后保存到内存并写入文件
+    pub fn update(&self, settings: Settings) -> Result<(), GamepadError> {
+        settings.validate()?;
+        self.save(&settings)?;
+        if let Ok(mut current) = self.settings.write() {
+            *current = settings;
+            return Ok(());
+        }
+        Err(GamepadError::lock("settings"))
+    }
+
+    /// 在当前设置上修改部分字段并保存
+    pub fn modify(&self, f: impl FnOnce(&mut Settings)) -> Result<(), GamepadError> {
+        let mut settings = self.get();
+        f(&mut settings);
+        self.update(settings)
+    }
+
+    fn save(&self, settings: &Settings) -> Result<(), GamepadError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let content = serde_json::to_string_pretty(settings)?;
+        fs::write(path, content).map_err(GamepadError::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn invalid_fields_are_rejected_individually() {
+        let (settings, rejected) = Settings::from_value(json!({
+            "frame_rate": 60,
+            "log_size": 10,
+            "language": 3,
+            "is_record_log": true,
+        }));
+        assert_eq!(settings.frame_rate, 60);
+        assert_eq!(settings.log_size, DEFAULT_LOG_SIZE);
+        assert_eq!(settings.language, LANGUAGES[0]);
+        assert!(settings.is_record_log);
+
+        let mut keys: Vec<_> = rejected.iter().filter_map(|r| r.key.clone()).collect();
+        keys.sort();
+        assert_eq!(keys, ["language", "log_size"]);
+        let language = rejected
+            .iter()
+            .find(|r| r.key.as_deref() == Some("language"))
+            .unwrap();
+        assert!(matches!(language.error, GamepadError::InvalidData(_)));
+        let log_size = rejected
+            .iter()
+            .find(|r| r.key.as_deref() == Some("log_size"))
+            .unwrap();
+        assert!(matches!(log_size.error, GamepadError::InvalidArgument(_)));
+    }
+
+    #[test]
+    fn non_object_is_rejected() {
+        let (settings, rejected) = Settings::from_value(json!([1, 2]));
+        assert_eq!(settings.frame_rate, DEFAULT_FRAME_RATE);
+        assert_eq!(rejected.len(), 1);
+        assert!(rejected[0].key.is_none());
+    }
+}
@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+
+const RELEASE_FROM: f64 = 0.7; // 松开前的最小偏移
+const RELEASE_TO: f64 = 0.25; // 回到该半径以内视为已松开
+const MAX_RELEASE_TIME: u64 = 40_000; // us, 从偏移到回中超过该时间认为是手动回中
+const ANALYSIS_WINDOW: u64 = 250_000; // us, 松开后分析的时长
+const MIN_WINDOW: u64 = 100_000; // us, 下一次推动过早时放弃本次事件
+const REST_TAIL: u64 = 50_000; // us, 窗口末尾用于计算静止位置的时长
+const SETTLE_BAND: f64 = 0.05; // 与静止位置的距离小于该值视为稳定
+const CROSSING_BAND: f64 = 0.02; // 越过中心的迟滞，避免噪声被计为振荡
+const MAX_EVENTS: usize = 20; // 结果中保留的最近事件数
+
+/// 一次松开回中
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SnapbackEvent {
+    /// 开始回中的时间 (us)
+    pub timestamp: u64,
+    /// 松开前的方向 (rad)
+    pub direction: f64,
+    /// 越过中心后沿反方向的最大偏移（归一化单位）
+    pub overshoot: f64,
+    /// 越过中心的次数，1 表示只冲过一次就停下
+    pub oscillations: u32,
+    /// 从开始回中到稳定在静止位置附近的时间 (ms)，窗口内未稳定时为空
+    pub settling_time: Option<f64>,
+}
+
+/// 单个摇杆的回中分析
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SnapbackStats {
+    pub count: usize,
+    pub max_overshoot: f64,
+    pub avg_overshoot: f64,
+    pub avg_oscillations: f64,
+    /// 只统计已稳定的事件
+    pub avg_settling_time: Option<f64>,
+    pub max_settling_time: Option<f64>,
+    /// 窗口内未稳定的事件数
+    pub unsettled: usize,
+    /// 最近的事件，按时间排列
+    pub events: Vec<SnapbackEvent>,
+}
+
+impl SnapbackStats {
+    /// samples 为 (时间戳 us, x, y)，坐标已归一化到 [-1, 1]
+    pub fn analyze(samples: &[(u64, f64, f64)]) -> Self {
+        let mut events = Vec::new();
+        let mut last_high: Option<usize> = None;
+        let mut i = 0;
+        while i < samples.len() {
+            let (t, x, y) = samples[i];
+            let magnitude = x.hypot(y);
+            if magnitude >= RELEASE_FROM {
+                last_high = Some(i);
+            } else if magnitude <= RELEASE_TO {
+                if let Some(start) = last_high.take() {
+                    if t - samples[start].0 <= MAX_RELEASE_TIME {
+                        match analyze_release(samples, start) {
+                            Some((event, end)) => {
+                                events.extend(event);
+                                i = end;
+                                continue;
+                            }
+                            // 日志末尾的事件数据不足，等待下次分析
+                            None => break,
+                        }
+                    }
+                }
+            }
+            i += 1;
+        }
+        SnapbackStats::from_events(events)
+    }
+
+    fn from_events(mut events: Vec<SnapbackEvent>) -> Self {
+        let count = events.len();
+        if count == 0 {
+            return SnapbackStats::default();
+        }
+        let n = count as f64;
+        let settled: Vec<f64> = events.iter().filter_map(|e| e.settling_time).collect();
+        let stats = SnapbackStats {
+            count,
+            max_overshoot: events.iter().map(|e| e.overshoot).fold(0.0, f64::max),
+            avg_overshoot: events.iter().map(|e| e.overshoot).sum::<f64>() / n,
+            avg_oscillations: events.iter().map(|e| e.oscillations as f64).sum::<f64>() / n,
+            avg_settling_time: (!settled.is_empty())
+                .then(|| settled.iter().sum::<f64>() / settled.len() as f64),
+            max_settling_time: settled.iter().copied().reduce(f64::max),
+            unsettled: count - settled.len(),
+            events: Vec::new(),
+        };
+        let skip = count.saturating_sub(MAX_EVENTS);
+        SnapbackStats {
+            events: events.split_off(skip),
+            ..stats
+        }
+    }
+}
+
+/// 分析从 start（松开前最后一个大偏移样本）开始的回中过程，返回事件与窗口结束位置；
+/// 日志中数据不足时返回 None，窗口过短时事件为 None
+fn analyze_release(
+    samples: &[(u64, f64, f64)],
+    start: usize,
+) -> Option<(Option<SnapbackEvent>, usize)> {
+    let (t0, x0, y0) = samples[start];
+    let direction = y0.atan2(x0);
+    let (dx, dy) = (direction.cos(), direction.sin());
+
+    // 窗口在时长用尽或再次推动时结束
+    let mut end = start + 1;
+    while end < samples.len() {
+        let (t, x, y) = samples[end];
+        if t - t0 > ANALYSIS_WINDOW || (end > start + 1 && x.hypot(y) >= RELEASE_FROM) {
+            break;
+        }
+        end += 1;
+    }
+    let window = &samples[start..end];
+    let last = window.last()?.0;
+    if end == samples.len() && last - t0 < ANALYSIS_WINDOW {
+        return None;
+    }
+    if last - t0 < MIN_WINDOW {
+        return Some((None, end));
+    }
+
+    // 静止位置取窗口末尾的平均值，吸收摇杆本身的漂移
+    let tail: Vec<&(u64, f64, f64)> = window.iter().filter(|s| last - s.0 <= REST_TAIL).collect();
+    let rest_x = tail.iter().map(|s| s.1).sum::<f64>() / tail.len() as f64;
+    let rest_y = tail.iter().map(|s| s.2).sum::<f64>() / tail.len() as f64;
+
+    let mut overshoot: f64 = 0.0;
+    let mut oscillations = 0;
+    let mut side = 1.0;
+    let mut settled_at = t0;
+    for &(t, x, y) in window {
+        let (px, py) = (x - rest_x, y - rest_y);
+        let p = px * dx + py * dy;
+        overshoot = overshoot.max(-p);
+        if p * side < -CROSSING_BAND {
+            side = -side;
+            oscillations += 1;
+        }
+        if px.hypot(py) > SETTLE_BAND {
+            settled_at = t;
+        }
+    }
+    // 末尾仍有样本偏离静止位置，说明窗口内未稳定
+    let settling_time = (settled_at + REST_TAIL <= last).then(|| (settled_at - t0) as f64 / 1000.0);
+
+    Some((
+        Some(SnapbackEvent {
+            timestamp: t0,
+            direction,
+            overshoot,
+            oscillations,
+            settling_time,
+        }),
+        end,
+    ))
+}
+
+/// 左右摇杆的回中分析
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct StickSnapback {
+    pub left: SnapbackStats,
+    pub right: SnapbackStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1 ms 一个样本：推满到 10 ms，随后回中并冲过中心
+    fn release(return_step: u64) -> Vec<(u64, f64, f64)> {
+        let mut samples: Vec<(u64, f64, f64)> = (0..=10).map(|i| (i * 1000, 1.0, 0.0)).collect();
+        let path = [0.5, 0.1, -0.15, -0.08, 0.03];
+        for (i, x) in path.iter().enumerate() {
+            samples.push((10_000 + (i as u64 + 1) * return_step, *x, 0.0));
+        }
+        let last = samples.last().unwrap().0;
+        samples.extend((1..=400).map(|i| (last + i * 1000, 0.0, 0.0)));
+        samples
+    }
+
+    #[test]
+    fn overshoot_and_settling_time() {
+        let stats = SnapbackStats::analyze(&release(1000));
+        assert_eq!(stats.count, 1);
+        let event = &stats.events[0];
+        assert_eq!(event.timestamp, 10_000);
+        assert!(event.direction.abs() < 1e-9);
+        assert!((event.overshoot - 0.15).abs() < 1e-9);
+        // -0.15 与 0.03 两次越过中心
+        assert_eq!(event.oscillations, 2);
+        // 最后一个偏离静止位置超过 SETTLE_BAND 的样本在 14 ms
+        assert_eq!(event.settling_time, Some(4.0));
+        assert_eq!(stats.max_settling_time, Some(4.0));
+        assert_eq!(stats.unsettled, 0);
+    }
+
+    #[test]
+    fn slow_return_is_ignored() {
+        // 手动慢慢回中，不计为松开
+        let stats = SnapbackStats::analyze(&release(30_000));
+        assert_eq!(stats.count, 0);
+    }
+
+    #[test]
+    fn incomplete_window_is_deferred() {
+        let samples: Vec<_> = release(1000).into_iter().take(100).collect();
+        assert_eq!(SnapbackStats::analyze(&samples).count, 0);
+    }
+}
@@ -0,0 +1,236 @@
+use crate::util::input_wrapper::Axes;
+use rustfft::{num_complex::Complex, FftPlanner};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+// 顺序与回报率日志的 xyxy 一致
+const AXES: [Axes; 4] = [
+    Axes::LeftThumbX,
+    Axes::LeftThumbY,
+    Axes::RightThumbX,
+    Axes::RightThumbY,
+];
+const MIN_FFT_SIZE: usize = 64;
+const MAX_FFT_SIZE: usize = 4096;
+const INTERVAL_PERCENTILE: f64 = 0.1; // 去重后的日志有长间隔，取较短的间隔估计采样周期
+const BANDWIDTH_ENERGY: f64 = 0.95; // 有效带宽内包含的能量比例
+const PEAK_RATIO: f64 = 4.0; // 超过噪声底该倍数的局部最大值视为周期性成分
+const MIN_PEAK_MAGNITUDE: f64 = 1e-4;
+const MAX_PEAKS: usize = 5;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpectrumPeak {
+    /// Hz
+    pub frequency: f64,
+    /// 该频率成分的幅值（归一化单位）
+    pub magnitude: f64,
+}
+
+/// 单轴幅度谱，第 k 个值对应 k * resolution Hz（已去除直流分量）
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AxisSpectrum {
+    pub axis: String,
+    pub magnitudes: Vec<f64>,
+    /// 最强成分的频率 (Hz)
+    pub peak_frequency: f64,
+    /// 包含 95% 能量的频率上限 (Hz)
+    pub bandwidth: f64,
+    /// 幅值中位数
+    pub noise_floor: f64,
+    /// 明显高于噪声底的周期性成分，按幅值从大到小
+    pub peaks: Vec<SpectrumPeak>,
+}
+
+/// 各轴在记录窗口内的频谱（"spectrum" 事件）
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Spectrum {
+    /// 重采样后的采样率 (Hz)
+    pub sample_rate: f64,
+    /// 频率分辨率 (Hz)
+    pub resolution: f64,
+    /// FFT 点数
+    pub size: usize,
+    pub axes: Vec<AxisSpectrum>,
+}
+
+impl Spectrum {
+    /// samples 为回报率日志中归一化后的 xyxy；样本不足时返回 None
+    pub fn analyze(samples: &[(u64, [f64; 4])]) -> Option<Self> {
+        let mut intervals: Vec<u64> = samples
+            .windows(2)
+            .map(|w| w[1].0.saturating_sub(w[0].0))
+            .filter(|interval| *interval > 0)
+            .collect();
+        if intervals.is_empty() {
+            return None;
+        }
+        intervals.sort_unstable();
+        let period = intervals[(intervals.len() as f64 * INTERVAL_PERCENTILE) as usize];
+        let span = samples.last()?.0 - samples.first()?.0;
+        let available = (span / period) as usize + 1;
+        if available < MIN_FFT_SIZE {
+            return None;
+        }
+        let size = prev_power_of_two(available).min(MAX_FFT_SIZE);
+        let sample_rate = 1_000_000.0 / period as f64;
+
+        // 按最近 size 个周期重采样；日志去除了重复值，两次上报之间保持上一次的值
+        let end = samples.last()?.0;
+        let start = end - (size as u64 - 1) * period;
+        let mut grid = vec![[0.0; 4]; size];
+        let mut cursor = samples
+            .partition_point(|(t, _)| *t <= start)
+            .saturating_sub(1);
+        for (i, value) in grid.iter_mut().enumerate() {
+            let t = start + i as u64 * period;
+            while cursor + 1 < samples.len() && samples[cursor + 1].0 <= t {
+                cursor += 1;
+            }
+            *value = samples[cursor].1;
+        }
+
+        // Hann 窗，幅值按窗函数之和校正
+        let window: Vec<f64> = (0..size)
+            .map(|i| 0.5 - 0.5 * (2.0 * PI * i as f64 / size as f64).cos())
+            .collect();
+        let gain = window.iter().sum::<f64>();
+        let fft = FftPlanner::<f64>::new().plan_fft_forward(size);
+        let resolution = sample_rate / size as f64;
+
+        let axes = AXES
+            .iter()
+            .enumerate()
+            .map(|(axis, name)| {
+                let mean = grid.iter().map(|v| v[axis]).sum::<f64>() / size as f64;
+                let mut buffer: Vec<Complex<f64>> = grid
+                    .iter()
+                    .zip(&window)
+                    .map(|(v, w)| Complex::new((v[axis] - mean) * w, 0.0))
+                    .collect();
+                fft.process(&mut buffer);
+                let mut magnitudes: Vec<f64> = buffer[..size / 2 + 1]
+                    .iter()
+                    .map(|c| 2.0 * c.norm() / gain)
+                    .collect();
+                magnitudes[0] = 0.0;
+                axis_spectrum(name.to_string(), magnitudes, resolution)
+            })
+            .collect();
+
+        Some(Spectrum {
+            sample_rate,
+            resolution,
+            size,
+            axes,
+        })
+    }
+}
+
+fn axis_spectrum(axis: String, magnitudes: Vec<f64>, resolution: f64) -> AxisSpectrum {
+    let mut sorted: Vec<f64> = magnitudes[1..].to_vec();
+    sorted.sort_unstable_by(f64::total_cmp);
+    let noise_floor = sorted.get(sorted.len() / 2).copied().unwrap_or(0.0);
+
+    let peak_index = (1..magnitudes.len())
+        .max_by(|a, b| magnitudes[*a].total_cmp(&magnitudes[*b]))
+        .filter(|k| magnitudes[*k] > 0.0)
+        .unwrap_or(0);
+
+    let total = magnitudes.iter().map(|m| m * m).sum::<f64>();
+    let mut energy = 0.0;
+    let mut bandwidth_index = 0;
+    for (k, m) in magnitudes.iter().enumerate() {
+        energy += m * m;
+        if energy >= total * BANDWIDTH_ENERGY {
+            bandwidth_index = k;
+            break;
+        }
+    }
+
+    let mut peaks: Vec<SpectrumPeak> = (1..magnitudes.len().saturating_sub(1))
+        .filter(|k| {
+            let m = magnitudes[*k];
+            m > magnitudes[k - 1]
+                && m >= magnitudes[k + 1]
+                && m >= MIN_PEAK_MAGNITUDE
+                && m >= noise_floor * PEAK_RATIO
+        })
+        .map(|k| SpectrumPeak {
+            frequency: k as f64 * resolution,
+            magnitude: magnitudes[k],
+        })
+        .collect();
+    peaks.sort_by(|a, b| b.magnitude.total_cmp(&a.magnitude));
+    peaks.truncate(MAX_PEAKS);
+
+    AxisSpectrum {
+        axis,
+        peak_frequency: peak_index as f64 * resolution,
+        bandwidth: bandwidth_index as f64 * resolution,
+        noise_floor,
+        peaks,
+        magnitudes,
+    }
+}
+
+fn prev_power_of_two(n: usize) -> usize {
+    1 << (usize::BITS - 1 - n.leading_zeros())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1000 Hz 采样，左摇杆 X 为正弦，其余轴不动
+    fn sine(count: u64, frequency: f64, amplitude: f64) -> Vec<(u64, [f64; 4])> {
+        (0..count)
+            .map(|i| {
+                let t = i as f64 / 1000.0;
+                let x = amplitude * (2.0 * PI * frequency * t).sin();
+                (i * 1000, [x, 0.0, 0.0, 0.0])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sine_peak_frequency() {
+        // 频率正好落在第 20 个频点上
+        let frequency = 20.0 * 1000.0 / 1024.0;
+        let spectrum = Spectrum::analyze(&sine(1024, frequency, 0.5)).unwrap();
+        assert_eq!(spectrum.size, 1024);
+        assert!((spectrum.sample_rate - 1000.0).abs() < 1e-9);
+
+        let x = &spectrum.axes[0];
+        assert!((x.peak_frequency - frequency).abs() < 1e-9);
+        assert!((x.magnitudes[20] - 0.5).abs() < 1e-6);
+        assert_eq!(x.peaks.len(), 1);
+        assert!((x.peaks[0].frequency - frequency).abs() < 1e-9);
+        assert!(x.bandwidth <= frequency + spectrum.resolution);
+
+        // 不动的轴没有峰值
+        let y = &spectrum.axes[1];
+        assert_eq!(y.peak_frequency, 0.0);
+        assert!(y.peaks.is_empty());
+    }
+
+    #[test]
+    fn off_bin_sine_peaks_at_nearest_bin() {
+        let spectrum = Spectrum::analyze(&sine(2000, 50.3, 0.2)).unwrap();
+        assert_eq!(spectrum.size, 1024);
+        let x = &spectrum.axes[0];
+        assert!((x.peak_frequency - 50.3).abs() <= spectrum.resolution / 2.0);
+    }
+
+    #[test]
+    fn too_few_samples() {
+        assert!(Spectrum::analyze(&sine(MIN_FFT_SIZE as u64 - 1, 10.0, 0.5)).is_none());
+        assert!(Spectrum::analyze(&[]).is_none());
+    }
+
+    #[test]
+    fn power_of_two_below() {
+        assert_eq!(prev_power_of_two(1), 1);
+        assert_eq!(prev_power_of_two(1024), 1024);
+        assert_eq!(prev_power_of_two(2000), 1024);
+    }
+}
@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+const MICROSECOND: f64 = 1000000.0;
+const COVERAGE_GRID: usize = 20; // 覆盖率测试将触摸板划分为 20x20 个格子
+
+/// 单个触点，坐标归一化到 [0, 1]，原点在左上角
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct TouchContact {
+    /// 多点触控槽位编号
+    pub id: u32,
+    pub x: f64,
+    pub y: f64,
+    /// 手指是否按在触摸板上
+    pub pressed: bool,
+}
+
+/// 一次触摸板上报
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TouchpadReport {
+    /// 相对测试开始的时间 (us)
+    pub timestamp: u64,
+    pub contacts: Vec<TouchContact>,
+    /// 触摸板是否被按下（点击）
+    pub clicked: bool,
+}
+
+impl TouchpadReport {
+    fn is_touching(&self) -> bool {
+        self.contacts.iter().any(|contact| contact.pressed)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TouchpadStats {
+    pub sample_count: usize,
+    /// 只统计触摸期间的上报间隔，空闲时设备不上报
+    pub polling_rate_avg: f64,
+    pub polling_rate_min: f64,
+    pub polling_rate_max: f64,
+    /// 同时按下的最大触点数
+    pub max_contacts: usize,
+    /// 已触碰格子占比 [0, 1]
+    pub coverage: f64,
+    pub grid_size: usize,
+    /// 已触碰的格子 (列, 行)
+    pub covered_cells: Vec<(usize, usize)>,
+}
+
+/// 触摸板上报记录与覆盖率
+#[derive(Debug, Clone)]
+pub struct TouchpadLog {
+    reports: VecDeque<TouchpadReport>,
+    log_size: usize,
+    max_contacts: usize,
+    covered_cells: HashSet<(usize, usize)>,
+}
+
+impl TouchpadLog {
+    pub fn new(log_size: usize) -> Self {
+        TouchpadLog {
+            reports: VecDeque::with_capacity(log_size),
+            log_size,
+            max_contacts: 0,
+            covered_cells: HashSet::new(),
+        }
+    }
+
+    pub fn push(&mut self, report: TouchpadReport) {
+        let pressed = report.contacts.iter().filter(|contact| contact.pressed);
+        self.max_contacts = self.max_contacts.max(pressed.clone().count());
+        for contact in pressed {
+            self.covered_cells
+                .insert((cell(contact.x), cell(contact.y)));
+        }
+
+        while self.reports.len() >= self.log_size.max(1) {
+            self.reports.pop_front();
+        }
+        self.reports.push_back(report);
+    }
+
+    pub fn set_log_size(&mut self, log_size: usize) {
+        self.log_size = log_size;
+        while self.reports.len() > log_size {
+            self.reports.pop_front();
+        }
+    }
+
+    /// 清空记录，覆盖率测试重新开始
+    pub fn clear(&mut self) {
+        self.reports.clear();
+        self.max_contacts = 0;
+        self.covered_cells.clear();
+    }
+
+    pub fn last(&self) -> Option<&TouchpadReport> {
+        self.reports.back()
+    }
+
+    pub fn reports(&self) -> Vec<TouchpadReport> {
+        self.reports.iter().cloned().collect()
+    }
+
+    pub fn stats(&self) -> TouchpadStats {
+        let intervals: Vec<u64> = self
+            .reports
+            .iter()
+            .zip(self.reports.iter().skip(1))
+            .filter(|(a, b)| a.is_touching() && b.is_touching())
+            .map(|(a, b)| b.timestamp.saturating_sub(a.timestamp))
+            .filter(|interval| *interval > 0)
+            .collect();
+        let (polling_rate_avg, polling_rate_min, polling_rate_max) = if intervals.is_empty() {
+            (0.0, 0.0, 0.0)
+        } else {
+            let sum: u64 = intervals.iter().sum();
+            (
+                MICROSECOND * intervals.len() as f64 / sum as f64,
+                MICROSECOND / *intervals.iter().max().unwrap_or(&1) as f64,
+                MICROSECOND / *intervals.iter().min().unwrap_or(&1) as f64,
+            )
+        };
+
+        let mut covered_cells: Vec<(usize, usize)> = self.covered_cells.iter().copied().collect();
+        covered_cells.sort();
+        TouchpadStats {
+            sample_count: self.reports.len(),
+            polling_rate_avg,
+            polling_rate_min,
+            polling_rate_max,
+            max_contacts: self.max_contacts,
+            coverage: covered_cells.len() as f64 / (COVERAGE_GRID * COVERAGE_GRID) as f64,
+            grid_size: COVERAGE_GRID,
+            covered_cells,
+        }
+    }
+}
+
+fn cell(v: f64) -> usize {
+    ((v.clamp(0.0, 1.0) * COVERAGE_GRID as f64) as usize).min(COVERAGE_GRID - 1)
+}
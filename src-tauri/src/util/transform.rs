@@ -0,0 +1,465 @@
+use crate::util::error::GamepadError;
+use serde::{Deserialize, Serialize};
+
+const BEZIER_ITERATIONS: usize = 32; // 二分求解贝塞尔曲线参数 t 的迭代次数
+const MAX_LOOKUP_POINTS: usize = 64;
+
+/// 摇杆死区形状
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadzoneShape {
+    /// 两轴分别计算，斜向推动时会吸附到轴线
+    Axial,
+    /// 幅值小于内死区时归零，之外不缩放（有跳变）
+    Radial,
+    /// 幅值从内死区到外死区重新缩放到 [0, 1]
+    ScaledRadial,
+    /// 先按缩放径向处理，再对各分量做斜坡轴向处理，沿轴线推动时不会偏离轴线
+    Hybrid,
+}
+
+/// 响应曲线，输入输出均为 [0, 1] 的幅值
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseCurve {
+    Linear,
+    /// y = x^exponent
+    Power {
+        exponent: f64,
+    },
+    /// 端点为 (0, 0) 和 (1, 1) 的三次贝塞尔曲线，p1、p2 为控制点
+    Bezier {
+        p1: (f64, f64),
+        p2: (f64, f64),
+    },
+    /// (输入, 输出) 折线，首尾自动补 (0, 0) 和 (1, 1)
+    Lookup {
+        points: Vec<(f64, f64)>,
+    },
+}
+
+impl ResponseCurve {
+    pub fn validate(&self) -> Result<(), GamepadError> {
+        let in_unit = |v: f64| (0.0..=1.0).contains(&v);
+        match self {
+            ResponseCurve::Linear => Ok(()),
+            ResponseCurve::Power { exponent } if *exponent > 0.0 && exponent.is_finite() => Ok(()),
+            ResponseCurve::Power { exponent } => Err(GamepadError::invalid(format!(
+                "Curve exponent must be positive, got {}",
+                exponent
+            ))),
+            // 控制点横坐标在 [0, 1] 内才能保证 x(t) 单调
+            ResponseCurve::Bezier { p1, p2 } if in_unit(p1.0) && in_unit(p2.0) => Ok(()),
+            ResponseCurve::Bezier { .. } => Err(GamepadError::invalid(
+                "Bezier control point x must be within 0..=1",
+            )),
+            ResponseCurve::Lookup { points } => {
+                if points.len() > MAX_LOOKUP_POINTS {
+                    return Err(GamepadError::invalid(format!(
+                        "Lookup table must have at most {} points",
+                        MAX_LOOKUP_POINTS
+                    )));
+                }
+                if points.iter().any(|(x, y)| !in_unit(*x) || !in_unit(*y)) {
+                    return Err(GamepadError::invalid(
+                        "Lookup table points must be within 0..=1",
+                    ));
+                }
+                if points.windows(2).any(|w| w[1].0 <= w[0].0) {
+                    return Err(GamepadError::invalid(
+                        "Lookup table inputs must be strictly increasing",
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn apply(&self, x: f64) -> f64 {
+        let x = x.clamp(0.0, 1.0);
+        let y = match self {
+            ResponseCurve::Linear => x,
+            ResponseCurve::Power { exponent } => x.powf(*exponent),
+            ResponseCurve::Bezier { p1, p2 } => {
+                let bezier = |t: f64, a: f64, b: f64| {
+                    let u = 1.0 - t;
+                    3.0 * u * u * t * a + 3.0 * u * t * t * b + t * t * t
+                };
+                let (mut lo, mut hi) = (0.0, 1.0);
+                for _ in 0..BEZIER_ITERATIONS {
+                    let mid = (lo + hi) / 2.0;
+                    if bezier(mid, p1.0, p2.0) < x {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+                bezier((lo + hi) / 2.0, p1.1, p2.1)
+            }
+            ResponseCurve::Lookup { points } => {
+                let mut previous = (0.0, 0.0);
+                let mut y = None;
+                for point in points.iter().copied().chain([(1.0, 1.0)]) {
+                    if x <= point.0 {
+                        let span = point.0 - previous.0;
+                        let t = if span > 0.0 {
+                            (x - previous.0) / span
+                        } else {
+                            1.0
+                        };
+                        y = Some(previous.1 + (point.1 - previous.1) * t);
+                        break;
+                    }
+                    previous = point;
+                }
+                y.unwrap_or(previous.1)
+            }
+        };
+        y.clamp(0.0, 1.0)
+    }
+}
+
+/// 幅值从 [inner, outer] 缩放到 [0, 1]，再经过响应曲线与反死区
+fn shape_magnitude(
+    m: f64,
+    inner: f64,
+    outer: f64,
+    anti_deadzone: f64,
+    curve: &ResponseCurve,
+) -> f64 {
+    if m <= inner {
+        return 0.0;
+    }
+    let scaled = ((m - inner) / (outer - inner)).clamp(0.0, 1.0);
+    anti_deadzone + (1.0 - anti_deadzone) * curve.apply(scaled)
+}
+
+fn validate_zones(inner: f64, outer: f64, anti_deadzone: f64) -> Result<(), GamepadError> {
+    if !(0.0..1.0).contains(&inner) || outer <= inner || outer > 1.0 {
+        return Err(GamepadError::invalid(format!(
+            "Deadzones must satisfy 0 <= inner < outer <= 1, got {} and {}",
+            inner, outer
+        )));
+    }
+    if !(0.0..1.0).contains(&anti_deadzone) {
+        return Err(GamepadError::invalid(format!(
+            "Anti-deadzone must be within 0..1, got {}",
+            anti_deadzone
+        )));
+    }
+    Ok(())
+}
+
+/// 单个摇杆的变换：死区 -> 响应曲线 -> 反死区
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StickTransform {
+    pub shape: DeadzoneShape,
+    pub inner: f64,
+    /// 外死区：幅值达到该值即视为推满
+    pub outer: f64,
+    /// 反死区：离开死区后的最小输出，用于抵消游戏自带的死区
+    pub anti_deadzone: f64,
+    pub curve: ResponseCurve,
+}
+
+impl Default for StickTransform {
+    fn default() -> Self {
+        StickTransform {
+            shape: DeadzoneShape::ScaledRadial,
+            inner: 0.05,
+            outer: 1.0,
+            anti_deadzone: 0.0,
+            curve: ResponseCurve::Linear,
+        }
+    }
+}
+
+impl StickTransform {
+    pub fn validate(&self) -> Result<(), GamepadError> {
+        validate_zones(self.inner, self.outer, self.anti_deadzone)?;
+        self.curve.validate()
+    }
+
+    /// 输入为向右、向上为正的 [-1, 1] 坐标
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        let axial = |v: f64| {
+            v.signum()
+                * shape_magnitude(
+                    v.abs(),
+                    self.inner,
+                    self.outer,
+                    self.anti_deadzone,
+                    &self.curve,
+                )
+        };
+        let m = x.hypot(y);
+        let radial = |magnitude: f64| {
+            if m == 0.0 {
+                (0.0, 0.0)
+            } else {
+                (x / m * magnitude, y / m * magnitude)
+            }
+        };
+        let (x, y) = match self.shape {
+            DeadzoneShape::Axial => (axial(x), axial(y)),
+            DeadzoneShape::Radial => {
+                if m <= self.inner {
+                    (0.0, 0.0)
+                } else {
+                    let magnitude = (m / self.outer).min(1.0);
+                    radial(
+                        self.anti_deadzone
+                            + (1.0 - self.anti_deadzone) * self.curve.apply(magnitude),
+                    )
+                }
+            }
+            DeadzoneShape::ScaledRadial => radial(shape_magnitude(
+                m,
+                self.inner,
+                self.outer,
+                self.anti_deadzone,
+                &self.curve,
+            )),
+            DeadzoneShape::Hybrid => {
+                let (rx, ry) = radial(shape_magnitude(
+                    m,
+                    self.inner,
+                    self.outer,
+                    self.anti_deadzone,
+                    &self.curve,
+                ));
+                // 轴向死区宽度随另一轴的幅值线性增大，推到轴线附近时吸附到轴线，
+                // 单轴推动时不受影响
+                let scale_axis = |v: f64, other: f64| {
+                    let width = other.abs() * self.inner;
+                    if v.abs() <= width {
+                        0.0
+                    } else {
+                        v.signum() * (v.abs() - width) / (1.0 - width)
+                    }
+                };
+                let (ax, ay) = (scale_axis(rx, ry), scale_axis(ry, rx));
+                let am = ax.hypot(ay);
+                let rm = rx.hypot(ry);
+                if am == 0.0 {
+                    (0.0, 0.0)
+                } else {
+                    // 方向取轴向结果，幅值保持径向结果
+                    (ax / am * rm, ay / am * rm)
+                }
+            }
+        };
+        (x.clamp(-1.0, 1.0), y.clamp(-1.0, 1.0))
+    }
+}
+
+/// 扳机的变换，输入输出均为 [0, 1]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TriggerTransform {
+    pub inner: f64,
+    pub outer: f64,
+    pub anti_deadzone: f64,
+    pub curve: ResponseCurve,
+}
+
+impl Default for TriggerTransform {
+    fn default() -> Self {
+        TriggerTransform {
+            inner: 0.0,
+            outer: 1.0,
+            anti_deadzone: 0.0,
+            curve: ResponseCurve::Linear,
+        }
+    }
+}
+
+impl TriggerTransform {
+    pub fn validate(&self) -> Result<(), GamepadError> {
+        validate_zones(self.inner, self.outer, self.anti_deadzone)?;
+        self.curve.validate()
+    }
+
+    pub fn apply(&self, value: f64) -> f64 {
+        shape_magnitude(
+            value.clamp(0.0, 1.0),
+            self.inner,
+            self.outer,
+            self.anti_deadzone,
+            &self.curve,
+        )
+    }
+}
+
+/// 每个摇杆与扳机的变换设置，保存在设备配置中
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct TransformSettings {
+    pub left_stick: StickTransform,
+    pub right_stick: StickTransform,
+    pub left_trigger: TriggerTransform,
+    pub right_trigger: TriggerTransform,
+}
+
+impl TransformSettings {
+    pub fn validate(&self) -> Result<(), GamepadError> {
+        self.left_stick.validate()?;
+        self.right_stick.validate()?;
+        self.left_trigger.validate()?;
+        self.right_trigger.validate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-6;
+
+    fn stick(shape: DeadzoneShape) -> StickTransform {
+        StickTransform {
+            shape,
+            inner: 0.2,
+            outer: 1.0,
+            anti_deadzone: 0.0,
+            curve: ResponseCurve::Linear,
+        }
+    }
+
+    fn assert_close(actual: (f64, f64), expected: (f64, f64)) {
+        assert!(
+            (actual.0 - expected.0).abs() < EPSILON && (actual.1 - expected.1).abs() < EPSILON,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn axial_deadzone() {
+        let transform = stick(DeadzoneShape::Axial);
+        assert_close(transform.apply(0.1, 0.6), (0.0, 0.5));
+        assert_close(transform.apply(-0.6, -0.1), (-0.5, 0.0));
+        assert_close(transform.apply(1.0, 1.0), (1.0, 1.0));
+    }
+
+    #[test]
+    fn radial_deadzone() {
+        let transform = stick(DeadzoneShape::Radial);
+        assert_close(transform.apply(0.1, 0.1), (0.0, 0.0));
+        // 离开死区后不缩放
+        assert_close(transform.apply(0.21, 0.0), (0.21, 0.0));
+        assert_close(transform.apply(0.3, 0.4), (0.3, 0.4));
+    }
+
+    #[test]
+    fn scaled_radial_deadzone() {
+        let transform = stick(DeadzoneShape::ScaledRadial);
+        assert_close(transform.apply(0.1, 0.1), (0.0, 0.0));
+        assert_close(transform.apply(0.6, 0.0), (0.5, 0.0));
+        // 幅值 0.5 缩放为 0.375，方向不变
+        assert_close(transform.apply(0.3, 0.4), (0.225, 0.3));
+        assert_close(transform.apply(0.0, -1.0), (0.0, -1.0));
+    }
+
+    #[test]
+    fn hybrid_deadzone() {
+        let transform = stick(DeadzoneShape::Hybrid);
+        assert_close(transform.apply(0.1, 0.1), (0.0, 0.0));
+        assert_close(transform.apply(0.6, 0.0), (0.5, 0.0));
+        // 靠近轴线时吸附到轴线，幅值与缩放径向一致
+        let m = (0.6f64.hypot(0.05) - 0.2) / 0.8;
+        assert_close(transform.apply(0.6, 0.05), (m, 0.0));
+        // 对角方向保持 45°
+        let m = (0.6f64.hypot(0.6) - 0.2) / 0.8;
+        let (x, y) = transform.apply(0.6, 0.6);
+        assert!((x - y).abs() < EPSILON);
+        assert!((x.hypot(y) - m).abs() < EPSILON);
+    }
+
+    #[test]
+    fn anti_deadzone_raises_minimum_output() {
+        let transform = StickTransform {
+            anti_deadzone: 0.2,
+            ..stick(DeadzoneShape::ScaledRadial)
+        };
+        assert_close(transform.apply(0.1, 0.0), (0.0, 0.0));
+        assert_close(transform.apply(0.2 + 1e-9, 0.0), (0.2, 0.0));
+        assert_close(transform.apply(0.6, 0.0), (0.6, 0.0));
+        assert_close(transform.apply(1.0, 0.0), (1.0, 0.0));
+    }
+
+    #[test]
+    fn power_curve() {
+        let curve = ResponseCurve::Power { exponent: 2.0 };
+        assert_eq!(curve.apply(0.0), 0.0);
+        assert!((curve.apply(0.5) - 0.25).abs() < EPSILON);
+        assert_eq!(curve.apply(1.0), 1.0);
+    }
+
+    #[test]
+    fn bezier_curve() {
+        // 关于中点对称的控制点经过 (0.5, 0.5)
+        let curve = ResponseCurve::Bezier {
+            p1: (0.42, 0.0),
+            p2: (0.58, 1.0),
+        };
+        assert!(curve.apply(0.0).abs() < EPSILON);
+        assert!((curve.apply(0.5) - 0.5).abs() < EPSILON);
+        assert!((curve.apply(1.0) - 1.0).abs() < EPSILON);
+
+        // 控制点横坐标为 0 时 x = t³，t = 0.5 处 y = 0.875
+        let curve = ResponseCurve::Bezier {
+            p1: (0.0, 1.0),
+            p2: (0.0, 1.0),
+        };
+        assert!((curve.apply(0.125) - 0.875).abs() < EPSILON);
+    }
+
+    #[test]
+    fn lookup_curve() {
+        let curve = ResponseCurve::Lookup {
+            points: vec![(0.5, 0.2)],
+        };
+        assert_eq!(curve.apply(0.0), 0.0);
+        assert!((curve.apply(0.25) - 0.1).abs() < EPSILON);
+        assert!((curve.apply(0.5) - 0.2).abs() < EPSILON);
+        assert!((curve.apply(0.75) - 0.6).abs() < EPSILON);
+        assert_eq!(curve.apply(1.0), 1.0);
+    }
+
+    #[test]
+    fn invalid_curves_rejected() {
+        assert!(ResponseCurve::Power { exponent: 0.0 }.validate().is_err());
+        assert!(ResponseCurve::Bezier {
+            p1: (1.5, 0.0),
+            p2: (0.5, 1.0),
+        }
+        .validate()
+        .is_err());
+        assert!(ResponseCurve::Lookup {
+            points: vec![(0.5, 0.2), (0.5, 0.4)],
+        }
+        .validate()
+        .is_err());
+        assert!(stick(DeadzoneShape::Axial).validate().is_ok());
+        assert!(StickTransform {
+            inner: 0.5,
+            outer: 0.4,
+            ..stick(DeadzoneShape::Axial)
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn trigger_transform() {
+        let transform = TriggerTransform {
+            inner: 0.1,
+            outer: 0.9,
+            ..TriggerTransform::default()
+        };
+        assert_eq!(transform.apply(0.05), 0.0);
+        assert!((transform.apply(0.5) - 0.5).abs() < EPSILON);
+        assert_eq!(transform.apply(0.95), 1.0);
+    }
+}
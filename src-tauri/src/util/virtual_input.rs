@@ -0,0 +1,140 @@
+use crate::util::error::GamepadError;
+use crate::util::input_wrapper::{Axes, Axis, Gamepad, RawInput};
+use crate::util::power::PowerInfo;
+use crate::util::sdl_guid::{create_guid, BUS_VIRTUAL, DRIVER_VIRTUAL};
+use rand::Rng;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_REPORT_RATE: f64 = 1000.0;
+const STEPS_PER_TURN: u64 = 360; // 每次上报转动 1 度，保证相邻上报不重复
+
+/// 虚拟设备的状态：每次上报摇杆沿圆周前进一步
+#[derive(Debug, Clone, Copy)]
+pub struct VirtualState {
+    pub thumbs: (i16, i16, i16, i16),
+    pub report_count: u64,
+    /// 当前状态对应上报的真实时刻
+    pub report_time: Option<Instant>,
+}
+
+#[derive(Debug)]
+struct VirtualClock {
+    period: Duration,
+    jitter: u64,
+    next_report: Instant,
+}
+
+/// 以已知回报率和抖动产生上报的虚拟设备，用于校准采样器
+#[derive(Debug)]
+pub struct VirtualInput {
+    state: Mutex<VirtualState>,
+    clock: Mutex<VirtualClock>,
+}
+
+impl VirtualInput {
+    /// report_rate: 上报频率 (Hz)；jitter: 每次上报时刻的均匀抖动幅度 (us)
+    pub fn with_config(report_rate: f64, jitter: u64) -> Self {
+        let period = Duration::from_secs_f64(1.0 / report_rate.max(1.0));
+        VirtualInput {
+            state: Mutex::new(VirtualState {
+                thumbs: (0, 0, 0, 0),
+                report_count: 0,
+                report_time: None,
+            }),
+            clock: Mutex::new(VirtualClock {
+                period,
+                jitter,
+                next_report: Instant::now() + period,
+            }),
+        }
+    }
+}
+
+impl RawInput<VirtualState, GamepadError> for VirtualInput {
+    fn new() -> Self {
+        VirtualInput::with_config(DEFAULT_REPORT_RATE, 0)
+    }
+
+    fn set_state(&self, state: VirtualState) {
+        if let Ok(mut current_state) = self.state.lock() {
+            *current_state = state;
+        }
+    }
+
+    fn get_state(&self) -> Result<VirtualState, GamepadError> {
+        if let Ok(current_state) = self.state.lock() {
+            return Ok(*current_state);
+        }
+        Err(GamepadError::lock("state"))
+    }
+
+    fn update(&self, _id: u32) -> Result<VirtualState, GamepadError> {
+        let (mut clock, mut state) = match (self.clock.lock(), self.state.lock()) {
+            (Ok(clock), Ok(state)) => (clock, state),
+            _ => return Err(GamepadError::lock("state")),
+        };
+
+        // 推进到当前时刻，期间多次上报只保留最新一次（与真实设备被轮询时一致）
+        let now = Instant::now();
+        let mut rng = rand::rng();
+        while clock.next_report <= now {
+            state.report_count += 1;
+            state.report_time = Some(clock.next_report);
+
+            let jitter = clock.jitter as i64;
+            let offset = if jitter > 0 {
+                rng.random_range(-jitter..=jitter)
+            } else {
+                0
+            };
+            let period = clock.period.as_micros() as i64;
+            clock.next_report += Duration::from_micros((period + offset).max(1) as u64);
+        }
+
+        let theta = (state.report_count % STEPS_PER_TURN) as f64 * 2.0 * PI / STEPS_PER_TURN as f64;
+        let axis_val = |v: f64| (v * i16::MAX as f64) as i16;
+        state.thumbs = (axis_val(theta.cos()), axis_val(theta.sin()), 0, 0);
+        Ok(*state)
+    }
+
+    fn all_device_id(&self) -> Vec<u32> {
+        vec![0]
+    }
+
+    fn get_controller(&self, id: u32) -> Result<Gamepad, GamepadError> {
+        let state = self.get_state()?;
+        let name = format!("Virtual {}", id);
+        let mut gamepad = Gamepad {
+            id,
+            guid: create_guid(BUS_VIRTUAL, 0, 0, 0, &name, DRIVER_VIRTUAL),
+            name,
+            vendor_id: None,
+            product_id: None,
+            version: None,
+            bus_type: None,
+            serial: None,
+            power: PowerInfo::unknown(),
+            axes: HashMap::new(),
+            buttons: HashMap::new(),
+        };
+        let (lx, ly, rx, ry) = state.thumbs;
+        for (axis, value) in [
+            (Axes::LeftThumbX, lx),
+            (Axes::LeftThumbY, ly),
+            (Axes::RightThumbX, rx),
+            (Axes::RightThumbY, ry),
+        ] {
+            gamepad
+                .axes
+                .insert(axis.clone(), Axis::from_i16(axis, value));
+        }
+        Ok(gamepad)
+    }
+
+    fn get_axis_val(&self) -> Option<(i16, i16, i16, i16)> {
+        self.state.lock().ok().map(|state| state.thumbs)
+    }
+}
@@ -4,6 +4,9 @@
  */
 import { reactive, watch } from 'vue'
 import { invoke } from '@tauri-apps/api/core'
+import { useI18n } from '../i18n'
+import type { LanguageCode } from '../i18n/messages'
+import type { GamepadError, HealthThresholds } from './useGamepadState'
 
 export interface AppSettings {
   frameRate: number
@@ -12,11 +15,36 @@ export interface AppSettings {
   isRecordLog: boolean
 }
 
+// 后端持久化的设置（settings.json）
+export interface PersistedSettings {
+  frame_rate: number
+  log_size: number
+  is_record_log: boolean
+  language: LanguageCode
+  sampler: Record<string, unknown>
+  health: HealthThresholds
+}
+
+// 读取设置文件时被忽略的字段，key 为空表示整个文件无法解析
+export interface RejectedSetting {
+  key: string | null
+  error: GamepadError
+}
+
+// 配置文件无法完整读取，原文件已备份到 backup
+export interface ProfileLoadError {
+  backup: string | null
+  rejected: string[]
+  error: GamepadError
+}
+
 export interface AppState {
   isInitializing: boolean
   hasError: boolean
   errorMessage: string
   isMainThreadRunning: boolean
+  // 读取保存的数据时出现的问题，不影响启动
+  loadWarnings: string[]
 }
 
 /**
@@ -28,7 +56,8 @@ export function useAppManager() {
     isInitializing: true,
     hasError: false,
     errorMessage: '',
-    isMainThreadRunning: false
+    isMainThreadRunning: false,
+    loadWarnings: []
   })
   
   // 应用设置
@@ -39,6 +68,9 @@ export function useAppManager() {
     isRecordLog: false
   })
   
+  const { currentLanguage, setLanguage } = useI18n()
+  let persistedSettings: PersistedSettings | null = null
+
   // 可用的设置选项
   const frameRateOptions = [30, 60, 120, 180]
   const logSizeOptions = [1000, 2000, 4000, 8000]
@@ -86,6 +118,22 @@ export function useAppManager() {
       }
     },
     
+    async getSettings(): Promise<PersistedSettings> {
+      return await invoke<PersistedSettings>("get_settings")
+    },
+
+    async getRejectedSettings(): Promise<RejectedSetting[]> {
+      return await invoke<RejectedSetting[]>("get_rejected_settings")
+    },
+
+    async getProfileLoadError(): Promise<ProfileLoadError | null> {
+      return await invoke<ProfileLoadError | null>("get_profile_load_error")
+    },
+
+    async updateSettings(settings: PersistedSettings): Promise<PersistedSettings> {
+      return await invoke<PersistedSettings>("update_settings", { settings })
+    },
+
     async cleanLog(): Promise<void> {
       try {
         console.log("Cleaning log data...")
@@ -98,6 +146,57 @@ export function useAppManager() {
     }
   }
   
+  // 读取后端保存的设置
+  const loadSettings = async (): Promise<void> => {
+    try {
+      persistedSettings = await tauriCommands.getSettings()
+      if (frameRateOptions.includes(persistedSettings.frame_rate)) {
+        appSettings.frameRate = persistedSettings.frame_rate
+      }
+      if (logSizeOptions.includes(persistedSettings.log_size)) {
+        appSettings.logSize = persistedSettings.log_size
+      }
+      appSettings.isRecordLog = persistedSettings.is_record_log
+      setLanguage(persistedSettings.language)
+      const rejected = await tauriCommands.getRejectedSettings()
+      appState.loadWarnings.push(
+        ...rejected.map(({ key, error }) =>
+          key
+            ? `Setting "${key}" reset to default: ${error.message}`
+            : `Settings file reset to defaults: ${error.message}`
+        )
+      )
+      const profileError = await tauriCommands.getProfileLoadError()
+      if (profileError) {
+        const dropped = profileError.rejected.length > 0
+          ? `dropped ${profileError.rejected.join(', ')}`
+          : 'all profiles reset'
+        const backup = profileError.backup ? `, original saved to ${profileError.backup}` : ''
+        appState.loadWarnings.push(
+          `Profiles could not be fully loaded (${dropped}${backup}): ${profileError.error.message}`
+        )
+      }
+    } catch (error) {
+      console.error("Error loading settings:", error)
+    }
+  }
+
+  // 保存当前设置到后端
+  const saveSettings = async (): Promise<void> => {
+    if (!persistedSettings) return
+    try {
+      persistedSettings = await tauriCommands.updateSettings({
+        ...persistedSettings,
+        frame_rate: appSettings.frameRate,
+        log_size: appSettings.logSize,
+        is_record_log: appSettings.isRecordLog,
+        language: currentLanguage.value
+      })
+    } catch (error) {
+      console.error("Error saving settings:", error)
+    }
+  }
+
   // 应用程序初始化
   const initializeApp = async (): Promise<void> => {
     try {
@@ -107,6 +206,11 @@ export function useAppManager() {
       
       console.log("Initializing application...")
       
+      // 0. 读取保存的设置
+      if (!persistedSettings) {
+        await loadSettings()
+      }
+      
       // 1. 设置日志大小
       await tauriCommands.setLogSize(appSettings.logSize)
       
@@ -159,6 +263,7 @@ export function useAppManager() {
   const updateFrameRate = async (newFrameRate: number): Promise<void> => {
     if (frameRateOptions.includes(newFrameRate) && newFrameRate !== appSettings.frameRate) {
       appSettings.frameRate = newFrameRate
+      await saveSettings()
       await restartMainThread()
     }
   }
@@ -167,6 +272,7 @@ export function useAppManager() {
   const updateLogSize = async (newLogSize: number): Promise<void> => {
     if (logSizeOptions.includes(newLogSize) && newLogSize !== appSettings.logSize) {
       appSettings.logSize = newLogSize
+      await saveSettings()
       await restartMainThread()
     }
   }
@@ -188,6 +294,7 @@ export function useAppManager() {
   const updateIsRecordLog = async (isRecordLog: boolean): Promise<void> => {
     if (isRecordLog !== appSettings.isRecordLog) {
       appSettings.isRecordLog = isRecordLog
+      await saveSettings()
       await restartMainThread()
     }
   }
@@ -206,6 +313,11 @@ export function useAppManager() {
     }
   )
   
+  // 语言切换时保存
+  watch(currentLanguage, () => {
+    saveSettings()
+  })
+  
   // 应用关闭时的清理
   const cleanup = async (): Promise<void> => {
     try {
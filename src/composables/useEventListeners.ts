@@ -4,7 +4,7 @@
  */
 import { onMounted, onBeforeUnmount } from 'vue'
 import { listen, type UnlistenFn } from '@tauri-apps/api/event'
-import type { GamepadInfo, PollingRateResult } from './useGamepadState'
+import type { GamepadInfo, PollingRateResult, Spectrum, StickGates } from './useGamepadState'
 
 export interface OutputLog {
   timestamp: number
@@ -99,6 +99,28 @@ export function useEventListeners() {
       })
       unlistenFunctions.push(unlistenJoystickLevels)
       
+      // 5. 摇杆外框分析事件 - 低频更新
+      const gateAnalysisThrottled = createThrottle((data: StickGates) => {
+        const callback = eventCallbacks.get('gate_analysis')
+        if (callback) callback(data)
+      }, 100) // ~10fps
+      
+      const unlistenGateAnalysis = await listen("gate_analysis", (event) => {
+        if (event.payload) {
+          gateAnalysisThrottled(event.payload as StickGates)
+        }
+      })
+      unlistenFunctions.push(unlistenGateAnalysis)
+      
+      // 6. 摇杆频谱事件 - 后端每 500ms 发送一次
+      const unlistenSpectrum = await listen("spectrum", (event) => {
+        const callback = eventCallbacks.get('spectrum')
+        if (event.payload && callback) {
+          callback(event.payload as Spectrum)
+        }
+      })
+      unlistenFunctions.push(unlistenSpectrum)
+      
       console.log("All event listeners initialized successfully")
     } catch (error) {
       console.error("Failed to initialize event listeners:", error)
@@ -7,26 +7,196 @@ import { invoke } from '@tauri-apps/api/core'
 
 export interface AxisData {
   axis: string
+  /** 按设备原生范围归一化到 [-1, 1] */
   value: number
+  /** 设备上报的原生值 */
+  raw: number
+  /** 按设备配置校准后的值，未启用校准时为空 */
+  calibrated?: number | null
+  /** 经过死区与响应曲线变换后的值，未启用变换时为空 */
+  transformed?: number | null
+}
+
+/** 单轴校准：calibrated = (value - offset) * scale */
+export interface AxisCalibration {
+  offset: number
+  scale: number
+}
+
+export interface AxisProgress {
+  axis: string
+  centre: number
+  min: number
+  max: number
+}
+
+/** 摇杆校准状态（"calibration" 事件） */
+export interface CalibrationStatus {
+  phase: 'centre' | 'range' | 'done'
+  centre_samples: number
+  range_samples: number
+  axes: AxisProgress[]
+  ready: boolean
+  result: Record<string, AxisCalibration>
+}
+
+export type DeadzoneShape = 'axial' | 'radial' | 'scaled_radial' | 'hybrid'
+
+/** 响应曲线，贝塞尔曲线端点固定为 (0, 0) 和 (1, 1) */
+export type ResponseCurve =
+  | { type: 'linear' }
+  | { type: 'power'; exponent: number }
+  | { type: 'bezier'; p1: [number, number]; p2: [number, number] }
+  | { type: 'lookup'; points: [number, number][] }
+
+export interface StickTransform {
+  shape: DeadzoneShape
+  inner: number
+  outer: number
+  anti_deadzone: number
+  curve: ResponseCurve
+}
+
+export interface TriggerTransform {
+  inner: number
+  outer: number
+  anti_deadzone: number
+  curve: ResponseCurve
+}
+
+/** set_transform 的参数，保存在设备配置中 */
+export interface TransformSettings {
+  left_stick: StickTransform
+  right_stick: StickTransform
+  left_trigger: TriggerTransform
+  right_trigger: TriggerTransform
 }
 
 export interface ButtonData {
   button: string
   is_pressed: boolean
   value: number
+  /** 扳机经过死区与响应曲线变换后的值 */
+  transformed?: number | null
+}
+
+export interface PowerInfo {
+  source: 'wired' | 'wireless' | 'unknown'
+  level: 'empty' | 'low' | 'medium' | 'full' | 'unknown'
+  percentage?: number | null
+  charging: 'charging' | 'discharging' | 'full' | 'not_charging' | 'unknown'
+}
+
+/** IMU 上报：加速度 (g)、角速度 (deg/s) */
+export interface MotionSample {
+  timestamp: number
+  accel: [number, number, number]
+  gyro: [number, number, number]
+}
+
+export interface MotionStats {
+  sample_count: number
+  polling_rate_avg: number
+  polling_rate_min: number
+  polling_rate_max: number
+  accel_noise: [number, number, number]
+  gyro_noise: [number, number, number]
+  gyro_drift: [number, number, number]
+  stationary: boolean
+}
+
+/** 触摸板触点，坐标归一化到 [0, 1] */
+export interface TouchContact {
+  id: number
+  x: number
+  y: number
+  pressed: boolean
+}
+
+export interface TouchpadStats {
+  sample_count: number
+  polling_rate_avg: number
+  polling_rate_min: number
+  polling_rate_max: number
+  max_contacts: number
+  coverage: number
+  grid_size: number
+  covered_cells: [number, number][]
+}
+
+/** 震动序列中的一步，强度范围 [0, 1]，时长单位 ms */
+export type RumbleStep =
+  | { type: 'constant'; low_frequency: number; high_frequency: number; duration: number }
+  | { type: 'ramp'; from: [number, number]; to: [number, number]; duration: number }
+  | { type: 'pulse'; low_frequency: number; high_frequency: number; on: number; off: number; count: number }
+  | { type: 'pause'; duration: number }
+
+export interface RumblePattern {
+  steps: RumbleStep[]
+  repeat?: number
+}
+
+export interface LedInfo {
+  player_count: number
+  player?: number | null
+  player_settable: boolean
+  lightbar: boolean
+}
+
+/** 逻辑轴对应的原生轴范围 */
+export interface AxisCapability {
+  name: string
+  source?: string | null
+  min: number
+  max: number
+  flat: number
+  fuzz: number
+  resolution: number
+}
+
+export type TriggerType = 'analog' | 'digital' | 'none'
+
+/** 设备实际具备的轴、按键与附加功能 */
+export interface DeviceCapabilities {
+  axes: AxisCapability[]
+  buttons: string[]
+  left_trigger: TriggerType
+  right_trigger: TriggerType
+  rumble: boolean
+  motion: boolean
+  touchpad: boolean
+  battery: boolean
+  leds: LedInfo
 }
 
 export interface GamepadInfo {
   id: number
+  profile_key: string
   name: string
   vendor_id?: number
   product_id?: number
   guid: string
-  power_info: string
+  power: PowerInfo
+  connection: 'connected' | 'disconnected' | 'error'
+  motion?: MotionSample | null
+  touchpad?: TouchContact[] | null
   axes: Record<string, AxisData>
   buttons: Record<string, ButtonData>
 }
 
+/** 后端命令返回的错误，kind 用于区分错误种类 */
+export interface GamepadError {
+  kind:
+    | 'device_not_connected'
+    | 'lock_poisoned'
+    | 'backend_unavailable'
+    | 'permission_denied'
+    | 'io'
+    | 'invalid_argument'
+    | 'invalid_data'
+  message: string
+}
+
 export interface PollingRateResult {
   polling_rate_avg: number
   polling_rate_min: number
@@ -35,6 +205,7 @@ export interface PollingRateResult {
   drop_rate: number
   avg_error_r: number
   avg_error_l: number
+  measurement_confidence: 'unknown' | 'low' | 'medium' | 'high' | 'exact'
 }
 
 export interface HistoryPoint {
@@ -42,18 +213,164 @@ export interface HistoryPoint {
   y: number
 }
 
+export type GateShape = 'circle' | 'square' | 'octagon' | 'rounded_square'
+
+export interface GateFit {
+  shape: GateShape
+  scale: number
+  rms_error: number
+  score: number
+}
+
+/** 单个摇杆的外框分析，轮廓点为 [-1, 1] 坐标 */
+export interface GateAnalysis {
+  coverage: number
+  /** 推动范围不足时为空 */
+  shape: GateShape | null
+  score: number
+  fits: GateFit[]
+  measured: [number, number][]
+  fitted: [number, number][]
+}
+
+/** "gate_analysis" 事件 */
+export interface StickGates {
+  left: GateAnalysis
+  right: GateAnalysis
+}
+
+/** 一次松开回中，时间单位 ms（timestamp 为 us） */
+export interface SnapbackEvent {
+  timestamp: number
+  direction: number
+  overshoot: number
+  oscillations: number
+  settling_time: number | null
+}
+
+export interface SnapbackStats {
+  count: number
+  max_overshoot: number
+  avg_overshoot: number
+  avg_oscillations: number
+  avg_settling_time: number | null
+  max_settling_time: number | null
+  unsettled: number
+  events: SnapbackEvent[]
+}
+
+/** get_snapback_analysis 的结果 */
+export interface StickSnapback {
+  left: SnapbackStats
+  right: SnapbackStats
+}
+
+/** source 轴移动时 target 轴的跟随程度，slope 为耦合系数 */
+export interface AxisCoupling {
+  source: string
+  target: string
+  correlation: number
+  slope: number
+  samples: number
+  valid: boolean
+  coupled: boolean
+}
+
+/** get_coupling_analysis 的结果 */
+export interface CouplingReport {
+  pairs: AxisCoupling[]
+  max_slope: number
+}
+
+export interface SpectrumPeak {
+  frequency: number
+  magnitude: number
+}
+
+/** 单轴幅度谱，magnitudes[k] 对应 k * resolution Hz */
+export interface AxisSpectrum {
+  axis: string
+  magnitudes: number[]
+  peak_frequency: number
+  bandwidth: number
+  noise_floor: number
+  peaks: SpectrumPeak[]
+}
+
+/** "spectrum" 事件 */
+export interface Spectrum {
+  sample_rate: number
+  resolution: number
+  size: number
+  axes: AxisSpectrum[]
+}
+
+/** 指标的警告与不合格阈值，数值越大越差 */
+export interface Threshold {
+  warn: number
+  fail: number
+}
+
+export interface HealthThresholds {
+  polling_jitter: Threshold
+  circularity_error: Threshold
+  drift: Threshold
+  noise: Threshold
+  inner_deadzone: Threshold
+  range_loss: Threshold
+  button_chatter: Threshold
+}
+
+export type MetricStatus = 'pass' | 'warn' | 'fail' | 'unknown'
+
+export type Grade = 'A' | 'B' | 'C' | 'D' | 'F'
+
+export interface HealthMetric {
+  metric: string
+  /** 摇杆指标为 "left"/"right" */
+  target: string | null
+  value: number | null
+  threshold: Threshold
+  status: MetricStatus
+}
+
+export interface ButtonChatter {
+  button: string
+  presses: number
+  chatter: number
+  /** 相邻两次状态变化的最短间隔 (ms) */
+  min_interval: number | null
+}
+
+/** get_health_report / export_health_report 的结果 */
+export interface HealthReport {
+  timestamp: number
+  device_name: string
+  profile_key: string
+  vendor_id: number | null
+  product_id: number | null
+  polling_rate: PollingRateResult
+  metrics: HealthMetric[]
+  chatter: ButtonChatter[]
+  score: number
+  grade: Grade | null
+  passed: boolean
+}
+
 // 默认手柄数据工厂函数
 function createDefaultGamepad(id: number = 0): GamepadInfo {
   return {
     id,
+    profile_key: "",
     name: id === -1 ? "No Gamepad Connected" : "Loading...",
     guid: "",
-    power_info: id === -1 ? "N/A" : "Unknown",
+    power: { source: "unknown", level: "unknown", percentage: null, charging: "unknown" },
+    connection: id === -1 ? "disconnected" : "connected",
     axes: {
-      "LeftThumbX": { axis: "LeftThumbX", value: 0 },
-      "LeftThumbY": { axis: "LeftThumbY", value: 0 },
-      "RightThumbX": { axis: "RightThumbX", value: 0 },
-      "RightThumbY": { axis: "RightThumbY", value: 0 }
+      "LeftThumbX": { axis: "LeftThumbX", value: 0, raw: 0 },
+      "LeftThumbY": { axis: "LeftThumbY", value: 0, raw: 0 },
+      "RightThumbX": { axis: "RightThumbX", value: 0, raw: 0 },
+      "RightThumbY": { axis: "RightThumbY", value: 0, raw: 0 }
     },
     buttons: {
       "A": { button: "A", is_pressed: false, value: 0 },
@@ -84,7 +401,8 @@ function createDefaultPollingRateResult(): PollingRateResult {
     avg_interval: 0,
     drop_rate: 0,
     avg_error_l: 0,
-    avg_error_r: 0
+    avg_error_r: 0,
+    measurement_confidence: 'unknown'
   }
 }
 
@@ -98,6 +416,8 @@ export function useGamepadState() {
   
   // 使用 shallowRef 优化大对象性能
   const currentGamepad = shallowRef<GamepadInfo>(createDefaultGamepad(-1))
+  const capabilities = shallowRef<DeviceCapabilities | null>(null)
+  const gateAnalysis = shallowRef<StickGates | null>(null)
   const pollingRateData = reactive<Record<string, PollingRateResult>>({})
   
   // 初始化标记，确保只在第一次设置正确的默认值
@@ -146,6 +466,21 @@ export function useGamepadState() {
   watch(() => settings.logSize, () => {
     clearHistoryIfNeeded()
   })
+
+  // 切换手柄时重新读取设备能力，读取失败时按标准布局显示
+  watch(selectedGamepadId, async (id) => {
+    capabilities.value = null
+    gateAnalysis.value = null
+    if (id === -1) return
+    try {
+      const result = await invoke<DeviceCapabilities>("get_device_capabilities", { userId: id })
+      if (selectedGamepadId.value === id) {
+        capabilities.value = result
+      }
+    } catch (error) {
+      console.warn(`Failed to get capabilities of gamepad ${id}:`, error)
+    }
+  })
   
   // 计算属性
   const selectedPollingRateData = computed(() => {
@@ -171,8 +506,24 @@ export function useGamepadState() {
     return currentGamepad.value?.buttons[buttonKey]?.button || buttonKey
   }
   
+  // 启用校准时显示校准后的值
   const getAxisValue = (axisKey: string): number => {
-    return currentGamepad.value?.axes[axisKey]?.value || 0
+    const axis = currentGamepad.value?.axes[axisKey]
+    return axis?.calibrated ?? axis?.value ?? 0
+  }
+
+  // 原生值、归一化值、校准值与变换值并列显示
+  const formatAxisReadout = (axisKey: string): string => {
+    const axis = currentGamepad.value?.axes[axisKey]
+    if (!axis) return ''
+    const parts = [`raw ${axis.raw}`, `norm ${formatNumber(axis.value)}`]
+    if (axis.calibrated != null) {
+      parts.push(`cal ${formatNumber(axis.calibrated)}`)
+    }
+    if (axis.transformed != null) {
+      parts.push(`tf ${formatNumber(axis.transformed)}`)
+    }
+    return parts.join(' · ')
   }
   
   const formatButtonValue = (buttonKey: string): string => {
@@ -182,6 +533,16 @@ export function useGamepadState() {
   const formatNumber = (value: number): string => {
     return value.toFixed(2)
   }
+
+  // 供电方式 + 电量（有百分比时优先显示百分比）
+  const formatPower = (power: PowerInfo): string => {
+    if (power.source === 'wired' && power.level === 'unknown') {
+      return 'Wired'
+    }
+    const level = power.percentage != null ? `${power.percentage}%` : power.level
+    const charging = power.charging === 'charging' ? ' (charging)' : ''
+    return `${power.source} / ${level}${charging}`
+  }
   
   // 高效的历史数据更新函数
   let lastHistoryUpdate = 0
@@ -222,13 +583,24 @@ export function useGamepadState() {
     if (newData.id !== selectedGamepadId.value) {
       return // 忽略其他手柄的数据
     }
+
+    // 断开或出错时保留默认布局，只更新连接状态
+    if (newData.connection !== 'connected') {
+      currentGamepad.value = { ...createDefaultGamepad(newData.id), connection: newData.connection }
+      triggerRef(currentGamepad)
+      return
+    }
     
     // 浅比较检查是否真的有变化
     const current = currentGamepad.value
     if (current && 
         current.id === newData.id &&
         current.name === newData.name &&
-        current.power_info === newData.power_info) {
+        current.connection === newData.connection &&
+        current.power.source === newData.power.source &&
+        current.power.level === newData.power.level &&
+        current.power.percentage === newData.power.percentage &&
+        current.power.charging === newData.power.charging) {
       
       // 检查buttons和axes是否有实质变化
       let hasButtonChange = false
@@ -244,7 +616,9 @@ export function useGamepadState() {
       
       if (!hasButtonChange) {
         for (const key in newData.axes) {
-          if (current.axes[key]?.value !== newData.axes[key]?.value) {
+          if (current.axes[key]?.value !== newData.axes[key]?.value ||
+              current.axes[key]?.calibrated !== newData.axes[key]?.calibrated ||
+              current.axes[key]?.transformed !== newData.axes[key]?.transformed) {
             hasAxisChange = true
             break
           }
@@ -286,6 +660,12 @@ export function useGamepadState() {
     }
   }
   
+  // 外框分析只来自当前选中的手柄
+  const updateGateAnalysis = (data: StickGates) => {
+    if (selectedGamepadId.value === -1) return
+    gateAnalysis.value = data
+  }
+  
   // 手柄ID更新 - 修复断开连接问题，确保选中的手柄始终可用，特别处理初始化
   const updateGamepadIds = async (): Promise<number[]> => {
     try {
@@ -452,6 +832,8 @@ export function useGamepadState() {
     selectedGamepadId,
     gamepadIds,
     currentGamepad,
+    capabilities,
+    gateAnalysis,
     pollingRateData,
     appState,
     settings,
@@ -470,10 +852,13 @@ export function useGamepadState() {
     getButtonValue,
     getButtonName,
     getAxisValue,
+    formatAxisReadout,
     formatButtonValue,
     formatNumber,
+    formatPower,
     updateGamepadData,
     updatePollingRateData,
+    updateGateAnalysis,
     updateGamepadIds,
     selectGamepad,
     toggleHistoryDisplay,